@@ -1,25 +1,39 @@
+mod auth;
 mod constants;
 pub mod database;
 pub mod globaldb_endpoints;
 pub mod health;
 pub mod icons;
+pub mod logging;
 mod utils;
 
 use crate::blockchain::EvmInquirerManager;
 use crate::coingecko;
-use crate::database::DBHandler;
+use crate::database::SessionStore;
 use crate::globaldb;
+use crate::icons::TokenListRegistry;
+use crate::logging::{LogFileHandle, LogReloadHandle};
+use crate::nft_metadata::NftMetadata;
 use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::Mutex;
 
 #[derive(Clone)]
 pub struct AppState {
     pub data_dir: PathBuf,
     pub globaldb: Arc<globaldb::GlobalDB>,
     pub coingecko: Arc<coingecko::Coingecko>,
-    pub userdb: Arc<RwLock<DBHandler>>,
+    pub userdb: Arc<SessionStore>,
     pub active_tasks: Arc<Mutex<HashSet<String>>>,
     pub evm_manager: Arc<EvmInquirerManager>,
+    pub token_list_registry: Arc<TokenListRegistry>,
+    pub nft_metadata: Arc<NftMetadata>,
+    pub log_reload_handle: LogReloadHandle,
+    pub log_file_handle: Option<LogFileHandle>,
+    /// Rotation settings applied to a new file handed to
+    /// `LogFileHandle::change_log_file` by `/logging/file`, since that
+    /// endpoint only receives the new path, not a full rotation policy.
+    pub max_logfiles_num: usize,
+    pub max_size_in_mb: usize,
 }
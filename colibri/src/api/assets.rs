@@ -4,11 +4,14 @@ use std::sync::Arc;
 use axum::{extract::Json, extract::State, http::StatusCode, response::IntoResponse};
 use serde::Serialize;
 
+use crate::api::auth::AuthenticatedDb;
 use crate::api::schemas::assets::AssetsIdentifier;
 use crate::api::utils::ApiResponse;
 use crate::api::AppState;
+use crate::blockchain::{batch_erc20_metadata, parse_asset_identifier, AssetAddress, EvmInquirerManager};
 use crate::database::user_db::NftData;
 use crate::globaldb::{AssetMappings, CollectionInfo};
+use alloy::primitives::Address;
 
 #[derive(Serialize)]
 #[serde(untagged)]
@@ -19,6 +22,7 @@ enum AssetData {
 
 pub async fn get_assets_mappings(
     State(state): State<Arc<AppState>>,
+    auth: AuthenticatedDb,
     Json(payload): Json<AssetsIdentifier>,
 ) -> impl IntoResponse {
     #[derive(Serialize)]
@@ -28,7 +32,7 @@ pub async fn get_assets_mappings(
     }
 
     // Query assets from global database
-    let (asset_mappings, asset_collections) = match state
+    let (mut asset_mappings, asset_collections) = match state
         .globaldb
         .as_ref()
         .get_assets_mappings(&payload.identifiers)
@@ -47,21 +51,29 @@ pub async fn get_assets_mappings(
         }
     };
 
-    // Query NFTs from user database
-    let nft_mappings = {
-        let userdb = state.userdb.read().await;
-        match userdb.get_nft_mappings(payload.identifiers.clone()).await {
-            Ok(mappings) => mappings,
-            Err(e) => {
-                log::error!("Failed to query NFT mappings: {}", e);
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(ApiResponse::<Resp> {
-                        result: None,
-                        message: "Failed to query NFT mappings from database".to_string(),
-                    }),
-                );
-            }
+    // globaldb doesn't always have a symbol on file for an EVM token (e.g. one
+    // that was only ever discovered as a counterparty in some other user's
+    // transaction), so batch-read it on-chain for whichever of this request's
+    // tokens are missing one -- batched through Multicall3 per chain touched
+    // instead of one `eth_call` per token.
+    fill_missing_evm_symbols(&mut asset_mappings, &state.evm_manager).await;
+
+    // Query NFTs from the caller's unlocked user database
+    let nft_mappings = match auth
+        .handler
+        .get_nft_mappings(payload.identifiers.clone(), &state.nft_metadata)
+        .await
+    {
+        Ok(mappings) => mappings,
+        Err(e) => {
+            log::error!("Failed to query NFT mappings: {}", e);
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<Resp> {
+                    result: None,
+                    message: "Failed to query NFT mappings from database".to_string(),
+                }),
+            );
         }
     };
 
@@ -87,3 +99,47 @@ pub async fn get_assets_mappings(
         }),
     )
 }
+
+/// Fills in `symbol` for any EVM-token entry globaldb didn't have one on
+/// file for, reading it on-chain instead. Grouped by chain and sent through
+/// `batch_erc20_metadata` so a request touching dozens of unrecognized
+/// tokens on the same chain costs one `aggregate3` round trip per
+/// `MULTICALL_CHUNK_SIZE` calls (three calls per token) rather than one
+/// `eth_call` per token.
+async fn fill_missing_evm_symbols(
+    asset_mappings: &mut HashMap<String, AssetMappings>,
+    evm_manager: &EvmInquirerManager,
+) {
+    let mut by_chain: HashMap<u64, Vec<(String, Address)>> = HashMap::new();
+    for (identifier, mapping) in asset_mappings.iter() {
+        if !mapping.symbol.is_empty() {
+            continue;
+        }
+        let Some(parsed) = parse_asset_identifier(identifier) else {
+            continue;
+        };
+        let Some(chain_id) = parsed.chain_id() else {
+            continue;
+        };
+        if let AssetAddress::Evm(address) = parsed.contract_address {
+            by_chain.entry(chain_id).or_default().push((identifier.clone(), address));
+        }
+    }
+
+    for (chain_id, entries) in by_chain {
+        let Some(inquirer) = evm_manager.get_or_init_inquirer_by_chain_id(chain_id).await else {
+            continue;
+        };
+        let addresses: Vec<Address> = entries.iter().map(|(_, address)| *address).collect();
+        let Ok(metadata) = batch_erc20_metadata(inquirer.as_ref(), &addresses).await else {
+            continue;
+        };
+        for (identifier, address) in entries {
+            if let Some(info) = metadata.get(&address) {
+                if let Some(mapping) = asset_mappings.get_mut(&identifier) {
+                    mapping.symbol = info.symbol.clone();
+                }
+            }
+        }
+    }
+}
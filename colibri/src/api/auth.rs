@@ -0,0 +1,51 @@
+use axum::extract::FromRequestParts;
+use axum::http::{header, request::Parts, StatusCode};
+use axum::Json;
+use std::sync::Arc;
+
+use crate::api::utils::ApiResponse;
+use crate::api::AppState;
+use crate::database::DBHandler;
+
+/// Resolves the `Authorization: Bearer <token>` header to the user database
+/// it was issued for by `unlock_user`. Handlers that need an authenticated
+/// database connection take this as an argument instead of reading
+/// `state.userdb` directly, so adding a new authenticated endpoint is just a
+/// matter of taking `AuthenticatedDb` as an argument.
+pub struct AuthenticatedDb {
+    pub token: String,
+    pub handler: DBHandler,
+}
+
+impl FromRequestParts<Arc<AppState>> for AuthenticatedDb {
+    type Rejection = (StatusCode, Json<ApiResponse<()>>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(unauthorized)?;
+
+        let handler = state.userdb.get(token).await.ok_or_else(unauthorized)?;
+
+        Ok(AuthenticatedDb {
+            token: token.to_string(),
+            handler,
+        })
+    }
+}
+
+fn unauthorized() -> (StatusCode, Json<ApiResponse<()>>) {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(ApiResponse::<()> {
+            result: None,
+            message: "Missing, invalid, or expired session token".to_string(),
+        }),
+    )
+}
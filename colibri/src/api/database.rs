@@ -2,8 +2,10 @@ use axum::{extract::Json, extract::State, http::StatusCode, response::IntoRespon
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::api::auth::AuthenticatedDb;
 use crate::api::utils::ApiResponse;
 use crate::api::AppState;
+use crate::database::DBHandler;
 
 #[derive(Deserialize)]
 pub struct UnlockDatabase {
@@ -11,21 +13,16 @@ pub struct UnlockDatabase {
     password: String,
 }
 
+/// Opens the given user's database and registers it under a fresh session
+/// token, returned to the caller. Every other authenticated endpoint
+/// resolves its database connection from this token (via `AuthenticatedDb`)
+/// rather than from a single shared connection, so more than one user can be
+/// unlocked at the same time.
 pub async fn unlock_user(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<UnlockDatabase>,
 ) -> impl IntoResponse {
-    let mut db = state.userdb.write().await;
-    if db.client.is_some() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<String> {
-                result: None,
-                message: "DB already unlocked".to_string(),
-            }),
-        )
-            .into_response();
-    }
+    let mut handler = DBHandler::new();
 
     let db_path = state
         .data_dir
@@ -33,29 +30,30 @@ pub async fn unlock_user(
         .join(payload.username)
         .join("rotkehlchen.db");
 
-    match db.unlock(db_path, payload.password).await {
-        Ok(_) => (
-            StatusCode::OK,
-            Json(ApiResponse::<bool> {
-                result: Some(true),
-                message: "".to_string(),
-            }),
-        )
-            .into_response(),
-        Err(err) => (
+    if let Err(err) = handler.unlock(db_path, payload.password).await {
+        return (
             StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<bool> {
+            Json(ApiResponse::<String> {
                 result: None,
                 message: err.to_string(),
             }),
         )
-            .into_response(),
+            .into_response();
     }
+
+    let token = state.userdb.create(handler).await;
+    (
+        StatusCode::OK,
+        Json(ApiResponse::<String> {
+            result: Some(token),
+            message: "".to_string(),
+        }),
+    )
+        .into_response()
 }
 
-pub async fn get_ignored_assets(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let db = state.userdb.read().await;
-    match db.get_ignored_assets(false).await {
+pub async fn get_ignored_assets(auth: AuthenticatedDb) -> impl IntoResponse {
+    match auth.handler.get_ignored_assets(false).await {
         Ok(set) => {
             let ignored_assets: Vec<String> = set.into_iter().collect();
             (
@@ -78,31 +76,27 @@ pub async fn get_ignored_assets(State(state): State<Arc<AppState>>) -> impl Into
     }
 }
 
-// Logout the authenticated user by closing the user's DB connection
-pub async fn logout_user(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let mut db = state.userdb.write().await;
-    if db.client.is_none() {
-        return (
+/// Logs out the authenticated user by removing and closing their session.
+pub async fn logout_user(
+    State(state): State<Arc<AppState>>,
+    auth: AuthenticatedDb,
+) -> impl IntoResponse {
+    match state.userdb.remove(&auth.token).await {
+        Some(_) => (
+            StatusCode::OK,
+            Json(ApiResponse::<bool> {
+                result: Some(true),
+                message: "".to_string(),
+            }),
+        )
+            .into_response(),
+        None => (
             StatusCode::BAD_REQUEST,
             Json(ApiResponse::<bool> {
                 result: None,
                 message: "DB not unlocked".to_string(),
             }),
         )
-            .into_response();
-    }
-
-    // Explicitly close the SQLite connection if available, then drop the handle
-    if let Some(client) = &db.client {
-        std::mem::drop(client.close());
+            .into_response(),
     }
-    db.client = None;
-    (
-        StatusCode::OK,
-        Json(ApiResponse::<bool> {
-            result: Some(true),
-            message: "".to_string(),
-        }),
-    )
-        .into_response()
 }
@@ -1,5 +1,36 @@
-use axum::{http::StatusCode, response::IntoResponse};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use std::sync::Arc;
+
+use crate::api::utils::ApiResponse;
+use crate::api::AppState;
 
 pub async fn status() -> impl IntoResponse {
     (StatusCode::OK, "healthy").into_response()
 }
+
+/// Returns the last known liveness, latency, and head block for every RPC
+/// node currently configured, grouped by chain. Backed by the background
+/// health-monitor loop started from `EvmInquirerManager::initialize_rpc_nodes`.
+pub async fn node_health(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let snapshot = state.evm_manager.health_snapshot().await;
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            result: Some(snapshot),
+            message: "".to_string(),
+        }),
+    )
+}
+
+/// Reports whether the loaded `global.db` carried a detached signature that
+/// verified against the trusted keyring, so a client can surface a warning
+/// if the asset data isn't authenticated.
+pub async fn globaldb_health(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            result: Some(state.globaldb.verification_status),
+            message: "".to_string(),
+        }),
+    )
+}
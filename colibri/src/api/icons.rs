@@ -30,6 +30,10 @@ pub struct AssetIconCheck {
     force_refresh: Option<bool>,
     #[serde(default)]
     use_collection_icon: bool,
+    // for Uniswap V3/V4 position NFTs (animated SVGs), rasterize to a static
+    // PNG of this size instead of serving the raw SVG; ignored for every
+    // other asset. See `icons::query_icon_remotely`.
+    rasterize_size: Option<u32>,
 }
 
 /// The handler for the get icon endpoint
@@ -103,7 +107,7 @@ pub async fn check_icon(
                                     );
                                     return StatusCode::INTERNAL_SERVER_ERROR.into_response();
                                 };
-                                query_icon_from_payload(state, payload, path)
+                                query_icon_from_payload(state, payload, path, true)
                                     .await
                                     .into_response()
                             }
@@ -123,8 +127,13 @@ pub async fn check_icon(
                                 tokio::spawn(icons::query_icon_remotely(
                                     payload.asset_id,
                                     path,
+                                    state.data_dir.join("images/media"),
+                                    state.globaldb.clone(),
                                     state.coingecko.clone(),
                                     state.evm_manager.clone(),
+                                    state.token_list_registry.clone(),
+                                    payload.rasterize_size,
+                                    false,
                                 ));
                                 StatusCode::ACCEPTED.into_response()
                             }
@@ -147,7 +156,8 @@ pub async fn check_icon(
         None => {
             // There is no local reference to the file, query it. Ensure that if it is requested
             // again only one task handles it.
-            query_icon_from_payload(state, payload, path)
+            let force_refresh = payload.force_refresh.unwrap_or(false);
+            query_icon_from_payload(state, payload, path, force_refresh)
                 .await
                 .into_response()
         }
@@ -156,10 +166,16 @@ pub async fn check_icon(
 
 /// Helper function to update the status of the query in the shared state
 /// and start the query of an icon remotely.
+///
+/// `force_refresh` is threaded through separately from `payload` (rather
+/// than read back off it inside) since the caller already knows whether
+/// this invocation is a genuine user-requested force-refresh or just the
+/// no-local-file first query.
 async fn query_icon_from_payload(
     state: Arc<AppState>,
     payload: AssetIconCheck,
     path: std::path::PathBuf,
+    force_refresh: bool,
 ) -> StatusCode {
     let task_name = format!("{}_{}", QUERY_ICONS_TASK_PREFIX, payload.asset_id);
     let mut tasks_guard = state.active_tasks.lock().await;
@@ -175,8 +191,13 @@ async fn query_icon_from_payload(
             icons::query_icon_remotely(
                 payload.asset_id,
                 path,
+                state.data_dir.join("images/media"),
+                state.globaldb.clone(),
                 state.coingecko.clone(),
                 state.evm_manager.clone(),
+                state.token_list_registry.clone(),
+                payload.rasterize_size,
+                force_refresh,
             )
             .await;
             active_tasks.lock().await.remove(&task_key);
@@ -0,0 +1,96 @@
+use axum::{extract::Query, extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::api::utils::ApiResponse;
+use crate::api::AppState;
+use crate::logging::{build_env_filter, RotkiLogLevel};
+
+#[derive(Deserialize)]
+pub struct SetLogLevelRequest {
+    level: String,
+}
+
+#[derive(Deserialize)]
+pub struct SetLogFileRequest {
+    path: PathBuf,
+}
+
+/// Reloads the active `tracing_subscriber` `EnvFilter` at runtime.
+///
+/// This lets users bump colibri to `trace` while reproducing a bug and drop
+/// back to `info` afterwards without losing their running session. Returns
+/// the new effective level on success.
+pub async fn set_log_level(
+    State(state): State<Arc<AppState>>,
+    Query(payload): Query<SetLogLevelRequest>,
+) -> impl IntoResponse {
+    let level = match RotkiLogLevel::from_str(&payload.level) {
+        Ok(level) => level,
+        Err(message) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<String> {
+                    result: None,
+                    message,
+                }),
+            )
+        }
+    };
+
+    if let Err(e) = state.log_reload_handle.reload(build_env_filter(level, None)) {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<String> {
+                result: None,
+                message: format!("Failed to reload log level due to {}", e),
+            }),
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::<String> {
+            result: Some(level.to_string()),
+            message: "".to_string(),
+        }),
+    )
+}
+
+/// Redirects file-backed logging to a new path at runtime, e.g. when the
+/// user relocates colibri's data directory mid-session and wants log output
+/// to follow it there without restarting the process.
+///
+/// Returns a 400 if colibri isn't currently logging to a file (there's
+/// nothing to redirect) rather than silently creating one outside the
+/// rotation policy `config_logging` set up at startup.
+pub async fn set_log_file(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SetLogFileRequest>,
+) -> impl IntoResponse {
+    let Some(file_handle) = state.log_file_handle.as_ref() else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<String> {
+                result: None,
+                message: "Colibri isn't currently logging to a file".to_string(),
+            }),
+        );
+    };
+
+    file_handle.change_log_file(
+        payload.path.clone(),
+        state.max_logfiles_num,
+        state.max_size_in_mb,
+    );
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::<String> {
+            result: Some(payload.path.display().to_string()),
+            message: "".to_string(),
+        }),
+    )
+}
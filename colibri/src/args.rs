@@ -1,6 +1,6 @@
-use crate::logging::RotkiLogLevel;
+use crate::logging::{LogDestination, LogFormat, RotkiLogLevel};
 
-use clap::{Arg, ArgAction, Command};
+use clap::{Arg, Command};
 use std::path::PathBuf;
 
 // macro to get the datadir depending on os
@@ -41,13 +41,15 @@ fn default_data_dir(is_prod: bool) -> std::io::Result<PathBuf> {
 #[derive(Clone)]
 pub struct Args {
     pub data_directory: PathBuf,
-    pub logfile_path: PathBuf,
+    pub log_destination: LogDestination,
     pub port: u16,
-    pub log_to_stdout: bool,
     pub max_logfiles_num: usize,
     pub max_size_in_mb: usize,
     pub log_level: RotkiLogLevel,
-    pub api_cors: Vec<String>
+    pub log_filters: Option<String>,
+    pub log_format: LogFormat,
+    pub api_cors: Vec<String>,
+    pub token_list_urls: Vec<String>,
 }
 
 pub fn parse_args() -> Args {
@@ -76,17 +78,13 @@ pub fn parse_args() -> Args {
         .arg(
             Arg::new("logfile-path")
                 .long("logfile-path")
-                .value_parser(clap::value_parser!(PathBuf))
+                .value_parser(clap::value_parser!(LogDestination))
                 .default_value(PathBuf::from("colibri.log").into_os_string())
                 .value_hint(clap::ValueHint::DirPath)
-                .help("Sets the path for the colibri logfile"),
-        )
-        .arg(
-            Arg::new("log-to-stdout")
-                .long("log-to-stdout")
-                .required(false)
-                .action(ArgAction::SetTrue)
-                .help("Log to the stdout instead of the logfile"),
+                .help(
+                    "Sets where colibri logs are sent: a file path, \"-\"/\"stdout\" \
+                     for stdout or \"stderr\" for stderr",
+                ),
         )
         .arg(
             Arg::new("max-logfiles-num")
@@ -113,6 +111,31 @@ pub fn parse_args() -> Args {
                     ]
                 )),
         )
+        .arg(
+            Arg::new("log-filters")
+                .long("log-filters")
+                .required(false)
+                .help(
+                    "Comma separated list of per-target log directives, e.g. \
+                     \"rotki::blockchain=debug,rotki::coingecko=warn,hyper=off\". \
+                     Follows the same syntax as RUST_LOG and overrides --log-level \
+                     for the targets it mentions.",
+                ),
+        )
+        .arg(
+            Arg::new("log-format")
+                .long("log-format")
+                .value_parser(clap::value_parser!(LogFormat))
+                .default_value("compact")
+                .help(format!(
+                    "Log output format: {:?}",
+                    [
+                        LogFormat::Compact.to_string(),
+                        LogFormat::Full.to_string(),
+                        LogFormat::Json.to_string(),
+                    ]
+                )),
+        )
         .arg(
             Arg::new("max-size-in-mb")
                 .long("max-size-in-mb")
@@ -126,6 +149,15 @@ pub fn parse_args() -> Args {
                 .default_value("http://localhost:*/*")
                 .help("Comma separated list of domains for the API to accept cross origin requests.")
         )
+        .arg(
+            Arg::new("token-list-urls")
+                .long("token-list-urls")
+                .default_value("https://tokens.uniswap.org")
+                .help(
+                    "Comma separated list of Ethereum Token List (https://tokenlists.org/) URLs \
+                     used as an additional icon source.",
+                ),
+        )
         .get_matches();
 
     Args {
@@ -133,16 +165,26 @@ pub fn parse_args() -> Args {
             .get_one::<PathBuf>("data-directory")
             .unwrap()
             .clone(),
-        logfile_path: matches.get_one::<PathBuf>("logfile-path").unwrap().clone(),
+        log_destination: matches
+            .get_one::<LogDestination>("logfile-path")
+            .unwrap()
+            .clone(),
         port: *matches.get_one::<u16>("port").unwrap(),
-        log_to_stdout: *matches.get_one::<bool>("log-to-stdout").unwrap(),
         max_logfiles_num: *matches.get_one::<usize>("max-logfiles-num").unwrap(),
         log_level: *matches.get_one::<RotkiLogLevel>("log-level").unwrap(),
+        log_filters: matches.get_one::<String>("log-filters").cloned(),
+        log_format: *matches.get_one::<LogFormat>("log-format").unwrap(),
         max_size_in_mb: *matches.get_one::<usize>("max-size-in-mb").unwrap(),
         api_cors: matches.get_one::<String>("api-cors")
                 .unwrap()
                 .split(',')
                 .map(|s| s.trim().to_string())
                 .collect(),
+        token_list_urls: matches
+            .get_one::<String>("token-list-urls")
+            .unwrap()
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .collect(),
     }
 }
@@ -0,0 +1,158 @@
+use alloy::primitives::{Address, FixedBytes};
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::blockchain::EvmNodeInquirer;
+
+sol! {
+    #[sol(rpc)]
+    interface Erc20Metadata {
+        function name() external view returns (string memory);
+        function symbol() external view returns (string memory);
+        function decimals() external view returns (uint8);
+    }
+
+    // Some pre-ERC-20-standardization contracts (MKR being the canonical
+    // example) encode `name`/`symbol` as `bytes32` rather than `string`.
+    #[sol(rpc)]
+    interface Erc20MetadataBytes32 {
+        function name() external view returns (bytes32);
+        function symbol() external view returns (bytes32);
+    }
+}
+
+/// On-chain ERC-20 metadata, as read by `erc20_metadata`.
+#[derive(Debug, Clone)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// Runs a single contract read against every configured node via
+/// `EvmNodeInquirer::call_with_failover`, converting alloy's contract error
+/// into this crate's `Result<_, String>` convention so callers don't repeat
+/// `.map_err(|e| e.to_string())` at every call site.
+pub async fn eth_call<F, Fut, T>(inquirer: &EvmNodeInquirer, mut build: F) -> Result<T, String>
+where
+    F: FnMut(Arc<alloy::providers::DynProvider>) -> Fut,
+    Fut: Future<Output = Result<T, alloy::contract::Error>>,
+{
+    inquirer
+        .call_with_failover(|provider| {
+            let call = build(provider);
+            async move { call.await.map_err(|e| e.to_string()) }
+        })
+        .await
+}
+
+/// Decodes a `bytes32`-encoded `name()`/`symbol()` return value into a
+/// string, trimming the trailing null-byte padding used by non-compliant
+/// ERC-20s that predate the `string` return type.
+fn decode_bytes32_string(value: FixedBytes<32>) -> String {
+    let bytes = value.as_slice();
+    let end = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Reads `name`, `symbol`, and `decimals` for an ERC-20 contract via
+/// `eth_call`. Falls back to the `bytes32` encoding of `name`/`symbol` when
+/// the `string`-returning call reverts, covering the common non-compliant
+/// case.
+pub async fn erc20_metadata(
+    inquirer: &EvmNodeInquirer,
+    address: Address,
+) -> Result<TokenMetadata, String> {
+    let decimals = eth_call(inquirer, |provider| {
+        Erc20Metadata::new(address, provider).decimals().call()
+    })
+    .await?;
+
+    let name = match eth_call(inquirer, |provider| Erc20Metadata::new(address, provider).name().call())
+        .await
+    {
+        Ok(name) => name,
+        Err(_) => decode_bytes32_string(
+            eth_call(inquirer, |provider| {
+                Erc20MetadataBytes32::new(address, provider).name().call()
+            })
+            .await?,
+        ),
+    };
+
+    let symbol = match eth_call(inquirer, |provider| {
+        Erc20Metadata::new(address, provider).symbol().call()
+    })
+    .await
+    {
+        Ok(symbol) => symbol,
+        Err(_) => decode_bytes32_string(
+            eth_call(inquirer, |provider| {
+                Erc20MetadataBytes32::new(address, provider).symbol().call()
+            })
+            .await?,
+        ),
+    };
+
+    Ok(TokenMetadata {
+        name,
+        symbol,
+        decimals,
+    })
+}
+
+/// Batches `name`/`symbol`/`decimals` reads for many ERC-20 contracts into
+/// as few `aggregate3` calls as `EvmNodeInquirer::multicall`'s chunking
+/// allows, instead of three `eth_call`s per token. A token whose calls
+/// don't all succeed (e.g. not a contract, or a non-compliant `bytes32`
+/// encoding) is simply omitted from the result rather than failing the
+/// whole batch -- callers that need the `bytes32` fallback for a specific
+/// token can fall back to `erc20_metadata`.
+pub async fn batch_erc20_metadata(
+    inquirer: &EvmNodeInquirer,
+    addresses: &[Address],
+) -> Result<HashMap<Address, TokenMetadata>, String> {
+    let mut calls = Vec::with_capacity(addresses.len() * 3);
+    for &address in addresses {
+        calls.push((address, Erc20Metadata::nameCall {}.abi_encode().into()));
+        calls.push((address, Erc20Metadata::symbolCall {}.abi_encode().into()));
+        calls.push((address, Erc20Metadata::decimalsCall {}.abi_encode().into()));
+    }
+
+    let raw_results = inquirer.multicall(calls).await?;
+
+    let mut metadata = HashMap::with_capacity(addresses.len());
+    for (index, &address) in addresses.iter().enumerate() {
+        let name_data = raw_results.get(index * 3).cloned().flatten();
+        let symbol_data = raw_results.get(index * 3 + 1).cloned().flatten();
+        let decimals_data = raw_results.get(index * 3 + 2).cloned().flatten();
+
+        let (Some(name_data), Some(symbol_data), Some(decimals_data)) =
+            (name_data, symbol_data, decimals_data)
+        else {
+            continue;
+        };
+
+        let (Ok(name), Ok(symbol), Ok(decimals)) = (
+            Erc20Metadata::nameCall::abi_decode_returns(&name_data),
+            Erc20Metadata::symbolCall::abi_decode_returns(&symbol_data),
+            Erc20Metadata::decimalsCall::abi_decode_returns(&decimals_data),
+        ) else {
+            continue;
+        };
+
+        metadata.insert(
+            address,
+            TokenMetadata {
+                name,
+                symbol,
+                decimals,
+            },
+        );
+    }
+
+    Ok(metadata)
+}
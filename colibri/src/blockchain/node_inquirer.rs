@@ -1,13 +1,173 @@
+use alloy::primitives::{address, Address, Bytes};
 use alloy::providers::DynProvider;
+use alloy::providers::Provider;
 use alloy::providers::ProviderBuilder;
+use alloy::sol;
 use log::{debug, error};
+use rand::Rng;
+use serde::Serialize;
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tokio::time;
 
 use crate::blockchain::{RpcNode, SupportedBlockchain};
 use crate::globaldb::GlobalDB;
 
+sol! {
+    #[sol(rpc)]
+    interface Multicall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+        struct Result {
+            bool success;
+            bytes returnData;
+        }
+        function aggregate3(Call3[] calldata calls) external payable returns (Result[] memory returnData);
+    }
+}
+
+/// Canonical Multicall3 deployment address -- identical across every chain
+/// `EvmNodeInquirer` supports, since Multicall3 is deployed via a
+/// chain-agnostic CREATE2 factory.
+const MULTICALL3_ADDRESS: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+
+/// Batches larger than this are split across multiple `aggregate3` calls, to
+/// stay under a node's gas/response-size limits for a single `eth_call`.
+const MULTICALL_CHUNK_SIZE: usize = 50;
+
+/// Initial cooldown applied to a node after its first consecutive failure.
+const NODE_BACKOFF_BASE: Duration = Duration::from_secs(1);
+/// Cooldown never grows past this, however many consecutive failures a node has.
+const NODE_BACKOFF_CAP: Duration = Duration::from_secs(5 * 60);
+/// How often the background health-monitor loop probes every configured node.
+const HEALTH_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+/// A node more than this many blocks behind the highest head block observed
+/// across its chain this round is considered out of sync.
+const MAX_LAGGING_BLOCKS: u64 = 10;
+
+/// How long a node that reported the wrong `eth_chainId` is kept out of the
+/// rotation. Longer than the failure backoff cap: a chain id mismatch is a
+/// misconfigured endpoint (wrong node behind the wrong URL), not a transient
+/// fault, so retrying it quickly is pointless.
+const CHAIN_MISMATCH_COOLDOWN: Duration = Duration::from_secs(10 * 60);
+
+/// Tracks consecutive failures for one RPC node so `call_with_failover` can
+/// skip it while it's in its exponential-backoff cooldown, instead of
+/// hammering an endpoint that's already down or rate-limited. Also holds the
+/// liveness/latency/head-block state recorded by the background health probe.
+#[derive(Debug, Clone)]
+struct NodeHealth {
+    consecutive_failures: u32,
+    next_retry: Option<Instant>,
+    /// Whether the last health probe considered this node usable. Distinct
+    /// from the backoff cooldown above: this is toggled by the probe loop,
+    /// not by `call_with_failover`'s own failures.
+    active: bool,
+    latency: Option<Duration>,
+    head_block: Option<u64>,
+    /// Set when this node reported an `eth_chainId` that didn't match the
+    /// blockchain it's configured for. Kept separate from `next_retry` since
+    /// the cooldown is much longer -- see `CHAIN_MISMATCH_COOLDOWN`.
+    chain_mismatch_until: Option<Instant>,
+}
+
+impl Default for NodeHealth {
+    fn default() -> Self {
+        Self {
+            consecutive_failures: 0,
+            next_retry: None,
+            active: true,
+            latency: None,
+            head_block: None,
+            chain_mismatch_until: None,
+        }
+    }
+}
+
+impl NodeHealth {
+    fn is_available(&self) -> bool {
+        if !self.active {
+            return false;
+        }
+        if let Some(until) = self.chain_mismatch_until {
+            if Instant::now() < until {
+                return false;
+            }
+        }
+        match self.next_retry {
+            Some(next_retry) => Instant::now() >= next_retry,
+            None => true,
+        }
+    }
+
+    fn record_failure(&mut self) {
+        self.consecutive_failures += 1;
+        let exponent = self.consecutive_failures.saturating_sub(1).min(16);
+        let backoff = NODE_BACKOFF_BASE
+            .checked_mul(1u32 << exponent)
+            .unwrap_or(NODE_BACKOFF_CAP)
+            .min(NODE_BACKOFF_CAP);
+        self.next_retry = Some(Instant::now() + backoff);
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.next_retry = None;
+    }
+
+    fn record_chain_mismatch(&mut self) {
+        self.chain_mismatch_until = Some(Instant::now() + CHAIN_MISMATCH_COOLDOWN);
+    }
+}
+
+/// A point-in-time snapshot of one RPC node's observed health, as tracked by
+/// `call_with_failover`'s failure backoff and the background probe loop.
+/// Returned by the `/nodes/health` endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeStatus {
+    pub name: String,
+    pub endpoint: String,
+    pub owned: bool,
+    pub active: bool,
+    pub consecutive_failures: u32,
+    pub latency_ms: Option<u128>,
+    pub head_block: Option<u64>,
+}
+
+/// Orders `public` (non-owned) nodes via weighted-random shuffle: each draw
+/// picks among the remaining nodes with probability proportional to `weight`,
+/// so higher-weight nodes tend to sort earlier without always picking the
+/// same node first, spreading load across similarly-weighted endpoints.
+fn weighted_shuffle(mut public: Vec<RpcNode>) -> Vec<RpcNode> {
+    let mut rng = rand::rng();
+    let mut ordered = Vec::with_capacity(public.len());
+    while !public.is_empty() {
+        let total_weight: f64 = public.iter().map(|node| node.weight.max(0.0)).sum();
+        let pick = if total_weight <= 0.0 {
+            rng.random_range(0..public.len())
+        } else {
+            let mut remaining = rng.random::<f64>() * total_weight;
+            let mut pick = public.len() - 1;
+            for (index, node) in public.iter().enumerate() {
+                remaining -= node.weight.max(0.0);
+                if remaining <= 0.0 {
+                    pick = index;
+                    break;
+                }
+            }
+            pick
+        };
+        ordered.push(public.remove(pick));
+    }
+    ordered
+}
+
 /// A struct that manages connections to EVM RPC nodes for a specific blockchain
 pub struct EvmNodeInquirer {
     globaldb: Arc<GlobalDB>,
@@ -15,6 +175,7 @@ pub struct EvmNodeInquirer {
     pub blockchain: SupportedBlockchain,
     // Connected nodes
     provider_mapping: RwLock<HashMap<RpcNode, Box<DynProvider>>>,
+    node_health: RwLock<HashMap<RpcNode, NodeHealth>>,
 }
 
 impl EvmNodeInquirer {
@@ -24,6 +185,7 @@ impl EvmNodeInquirer {
             globaldb,
             rpc_nodes: RwLock::new(Vec::new()),
             provider_mapping: RwLock::new(HashMap::new()),
+            node_health: RwLock::new(HashMap::new()),
         };
 
         debug!("created EvmNodeInquirer for {}", blockchain.as_str());
@@ -33,7 +195,7 @@ impl EvmNodeInquirer {
     pub async fn update_rpc_nodes(&self) -> Result<(), String> {
         let nodes = self
             .globaldb
-            .get_rpc_nodes(self.blockchain)
+            .get_rpc_nodes(self.blockchain.clone())
             .await
             .map_err(|e| format!("Failed to get RPC nodes: {}", e))?;
 
@@ -45,7 +207,9 @@ impl EvmNodeInquirer {
     ///
     /// Checks if a connection to the specified RPC node already exists in the cache.
     /// If found, returns the existing provider; otherwise creates a new connection,
-    /// stores it in the cache, and returns it.
+    /// verifies it reports the expected `eth_chainId`, stores it in the cache, and
+    /// returns it. A node reporting the wrong chain id is put into a cooldown
+    /// (`NodeHealth::record_chain_mismatch`) instead of being cached.
     pub async fn get_or_create_node_connection(
         &self,
         node: &RpcNode,
@@ -59,16 +223,296 @@ impl EvmNodeInquirer {
             .parse()
             .map_err(|e| format!("Invalid endpoint URL: {}", e))?;
         let provider = DynProvider::new(ProviderBuilder::new().connect_http(endpoint));
+
+        let actual_chain_id = provider
+            .get_chain_id()
+            .await
+            .map_err(|e| format!("eth_chainId failed: {}", e))?;
+        let expected_chain_id = self.blockchain.chain_id();
+        if actual_chain_id != expected_chain_id {
+            self.node_health
+                .write()
+                .await
+                .entry(node.clone())
+                .or_default()
+                .record_chain_mismatch();
+            return Err(format!(
+                "Node '{}' reports chain id {} but {} expects {}",
+                node.name,
+                actual_chain_id,
+                self.blockchain.as_str(),
+                expected_chain_id
+            ));
+        }
+
         let mut mapping = self.provider_mapping.write().await;
         mapping.insert(node.clone(), Box::new(provider.clone()));
 
         Ok(Arc::new(provider))
     }
+
+    /// Issues a raw JSON-RPC call (`method`/`params`) against the first node
+    /// in the failover rotation that answers successfully, returning the
+    /// parsed response. Thin wrapper around `call_with_failover` for callers
+    /// that don't need a typed `alloy` provider method.
+    pub async fn call(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        let method = method.to_string();
+        self.call_with_failover(|provider| {
+            let method = method.clone();
+            let params = params.clone();
+            async move {
+                provider
+                    .client()
+                    .request(method, params)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+        })
+        .await
+    }
+
+    /// Probes every configured node with `eth_chainId` + `eth_blockNumber`,
+    /// recording liveness, latency, and the reported head block. A node that
+    /// fails the probe, or whose head lags more than `MAX_LAGGING_BLOCKS`
+    /// behind the highest head observed this round, is marked inactive and
+    /// excluded from `call_with_failover` until a later probe succeeds.
+    pub async fn probe_all_nodes(&self) {
+        let nodes = self.rpc_nodes.read().await.clone();
+        let mut results = Vec::with_capacity(nodes.len());
+        for node in &nodes {
+            results.push((node.clone(), self.probe_node(node).await));
+        }
+
+        let max_head = results
+            .iter()
+            .filter_map(|(_, result)| result.as_ref().ok().map(|(_, head)| *head))
+            .max();
+
+        let mut health_map = self.node_health.write().await;
+        for (node, result) in results {
+            let health = health_map.entry(node.clone()).or_default();
+            match result {
+                Ok((latency, head_block)) => {
+                    let lag = max_head.unwrap_or(head_block).saturating_sub(head_block);
+                    health.latency = Some(latency);
+                    health.head_block = Some(head_block);
+                    health.active = lag <= MAX_LAGGING_BLOCKS;
+                    if !health.active {
+                        debug!(
+                            "Node '{}' on {} is {} blocks behind the observed head, marking inactive",
+                            node.name,
+                            self.blockchain.as_str(),
+                            lag
+                        );
+                    }
+                }
+                Err(e) => {
+                    debug!(
+                        "Health probe failed for node '{}' on {}: {}",
+                        node.name,
+                        self.blockchain.as_str(),
+                        e
+                    );
+                    health.active = false;
+                }
+            }
+        }
+    }
+
+    async fn probe_node(&self, node: &RpcNode) -> Result<(Duration, u64), String> {
+        let provider = self.get_or_create_node_connection(node).await?;
+        let started = Instant::now();
+        provider
+            .get_chain_id()
+            .await
+            .map_err(|e| format!("eth_chainId failed: {}", e))?;
+        let head_block = provider
+            .get_block_number()
+            .await
+            .map_err(|e| format!("eth_blockNumber failed: {}", e))?;
+        Ok((started.elapsed(), head_block))
+    }
+
+    /// Returns the last known status of every configured node, combining
+    /// static `RpcNode` info with the health state tracked above.
+    pub async fn health_snapshot(&self) -> Vec<NodeStatus> {
+        let nodes = self.rpc_nodes.read().await.clone();
+        let health_map = self.node_health.read().await;
+        nodes
+            .into_iter()
+            .map(|node| {
+                let health = health_map.get(&node).cloned().unwrap_or_default();
+                NodeStatus {
+                    name: node.name,
+                    endpoint: node.endpoint,
+                    owned: node.owned,
+                    active: health.is_available(),
+                    consecutive_failures: health.consecutive_failures,
+                    latency_ms: health.latency.map(|d| d.as_millis()),
+                    head_block: health.head_block,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns `rpc_nodes` ordered for failover: owned nodes first (in their
+    /// existing order), then public nodes in a weighted-random order biased
+    /// towards higher `weight`.
+    async fn ordered_nodes(&self) -> Vec<RpcNode> {
+        let (owned, public): (Vec<_>, Vec<_>) =
+            self.rpc_nodes.read().await.clone().into_iter().partition(|node| node.owned);
+        let mut ordered = owned;
+        ordered.extend(weighted_shuffle(public));
+        ordered
+    }
+
+    /// Runs `call` against each configured RPC node in turn -- owned nodes
+    /// first, then public nodes in weighted-random order -- returning the
+    /// first successful result. Nodes still in their failure-backoff
+    /// cooldown are skipped. On error the failure is logged, the node's
+    /// cooldown is doubled, and the next node is tried; the accumulated
+    /// errors are returned only once every node has failed.
+    pub async fn call_with_failover<F, Fut, T>(&self, mut call: F) -> Result<T, String>
+    where
+        F: FnMut(Arc<DynProvider>) -> Fut,
+        Fut: Future<Output = Result<T, String>>,
+    {
+        let nodes = self.ordered_nodes().await;
+        if nodes.is_empty() {
+            return Err(format!(
+                "No RPC nodes configured for {}",
+                self.blockchain.as_str()
+            ));
+        }
+
+        let mut errors = Vec::new();
+        for node in nodes {
+            if !self
+                .node_health
+                .read()
+                .await
+                .get(&node)
+                .map(NodeHealth::is_available)
+                .unwrap_or(true)
+            {
+                continue;
+            }
+
+            let provider = match self.get_or_create_node_connection(&node).await {
+                Ok(provider) => provider,
+                Err(e) => {
+                    error!("Failed to connect to node '{}': {}", node.name, e);
+                    errors.push(format!("{}: {}", node.name, e));
+                    self.node_health
+                        .write()
+                        .await
+                        .entry(node.clone())
+                        .or_default()
+                        .record_failure();
+                    continue;
+                }
+            };
+
+            match call(provider).await {
+                Ok(value) => {
+                    self.node_health
+                        .write()
+                        .await
+                        .entry(node)
+                        .or_default()
+                        .record_success();
+                    return Ok(value);
+                }
+                Err(e) => {
+                    error!(
+                        "Call failed on node '{}' (endpoint: {}): {}",
+                        node.name, node.endpoint, e
+                    );
+                    errors.push(format!("{}: {}", node.name, e));
+                    self.node_health
+                        .write()
+                        .await
+                        .entry(node)
+                        .or_default()
+                        .record_failure();
+                }
+            }
+        }
+
+        Err(format!(
+            "All RPC nodes failed for {}: {}",
+            self.blockchain.as_str(),
+            errors.join("; ")
+        ))
+    }
+
+    /// Batches `(target, calldata)` reads into one or more `aggregate3`
+    /// calls to the canonical Multicall3 contract, run through
+    /// `call_with_failover`, splitting the batch into chunks of
+    /// `MULTICALL_CHUNK_SIZE` to stay under a node's response-size limits.
+    /// Each call is made with `allowFailure: true`, so one bad target
+    /// (e.g. not a contract, or reverting) only yields `None` at its index
+    /// rather than failing the whole batch; an `Err` here means the
+    /// multicall itself couldn't be made on any node.
+    pub async fn multicall(&self, calls: Vec<(Address, Bytes)>) -> Result<Vec<Option<Bytes>>, String> {
+        let mut results = Vec::with_capacity(calls.len());
+        for chunk in calls.chunks(MULTICALL_CHUNK_SIZE) {
+            let call3s: Vec<Multicall3::Call3> = chunk
+                .iter()
+                .map(|(target, call_data)| Multicall3::Call3 {
+                    target: *target,
+                    allowFailure: true,
+                    callData: call_data.clone(),
+                })
+                .collect();
+
+            let returned = self
+                .call_with_failover(|provider| {
+                    let call3s = call3s.clone();
+                    async move {
+                        Multicall3::new(MULTICALL3_ADDRESS, provider)
+                            .aggregate3(call3s)
+                            .call()
+                            .await
+                            .map_err(|e| e.to_string())
+                    }
+                })
+                .await?;
+
+            results.extend(returned.into_iter().map(|result| {
+                if result.success {
+                    Some(result.returnData)
+                } else {
+                    None
+                }
+            }));
+        }
+        Ok(results)
+    }
 }
 
+/// The well-known chains we always spin up an inquirer for, regardless of
+/// what's registered in globaldb.
+const WELL_KNOWN_CHAINS: [SupportedBlockchain; 7] = [
+    SupportedBlockchain::Ethereum,
+    SupportedBlockchain::Optimism,
+    SupportedBlockchain::PolygonPos,
+    SupportedBlockchain::ArbitrumOne,
+    SupportedBlockchain::Base,
+    SupportedBlockchain::Gnosis,
+    SupportedBlockchain::BinanceSc,
+];
+
 // A simple manager that stores EVM node inquirers for different chains
 pub struct EvmInquirerManager {
     inquirers: RwLock<HashMap<SupportedBlockchain, Arc<EvmNodeInquirer>>>,
+    /// Maps a chain id to the key it's stored under in `inquirers`, so
+    /// `get_or_init_inquirer_by_chain_id` doesn't need to scan every entry.
+    chain_index: RwLock<HashMap<u64, SupportedBlockchain>>,
     pub globaldb: Arc<GlobalDB>,
 }
 
@@ -76,33 +520,35 @@ impl EvmInquirerManager {
     pub fn new(globaldb: Arc<GlobalDB>) -> Self {
         Self {
             inquirers: RwLock::new(HashMap::new()),
+            chain_index: RwLock::new(HashMap::new()),
             globaldb,
         }
     }
 
+    /// Builds an inquirer for each well-known chain.
     pub async fn initialize_rpc_nodes(&self) {
-        let mut inquirers = self.inquirers.write().await;
-        for blockchain in [
-            SupportedBlockchain::Ethereum,
-            SupportedBlockchain::Optimism,
-            SupportedBlockchain::PolygonPos,
-            SupportedBlockchain::ArbitrumOne,
-            SupportedBlockchain::Base,
-            SupportedBlockchain::Gnosis,
-            SupportedBlockchain::BinanceSc,
-        ] {
-            let inquirer = inquirers.entry(blockchain).or_insert_with(|| {
-                Arc::new(EvmNodeInquirer::new(blockchain, self.globaldb.clone()))
-            });
-
-            if let Err(e) = inquirer.update_rpc_nodes().await {
-                error!(
-                    "Failed to update RPC nodes for {}: {}",
-                    blockchain.as_str(),
-                    e
-                );
-            }
+        for blockchain in WELL_KNOWN_CHAINS {
+            self.get_or_init_inquirer(blockchain).await;
+        }
+    }
+
+    async fn spin_up_inquirer(&self, blockchain: SupportedBlockchain) -> Arc<EvmNodeInquirer> {
+        let inquirer = Arc::new(EvmNodeInquirer::new(blockchain.clone(), self.globaldb.clone()));
+        if let Err(e) = inquirer.update_rpc_nodes().await {
+            error!(
+                "Failed to initialize RPC nodes for {}: {}",
+                blockchain.as_str(),
+                e
+            );
         }
+        tokio::spawn(health_monitor_loop(inquirer.clone()));
+
+        self.chain_index
+            .write()
+            .await
+            .insert(blockchain.chain_id(), blockchain.clone());
+        self.inquirers.write().await.insert(blockchain, inquirer.clone());
+        inquirer
     }
 
     pub async fn get_or_init_inquirer(
@@ -112,17 +558,43 @@ impl EvmInquirerManager {
         if let Some(inquirer) = self.inquirers.read().await.get(&blockchain) {
             return inquirer.clone();
         }
+        self.spin_up_inquirer(blockchain).await
+    }
 
-        let new_inquirer = Arc::new(EvmNodeInquirer::new(blockchain, self.globaldb.clone()));
-        if let Err(e) = new_inquirer.update_rpc_nodes().await {
-            error!(
-                "Failed to initialize RPC nodes for {}: {}",
-                blockchain.as_str(),
-                e
-            );
+    /// Looks up the inquirer for a numeric chain id. Only well-known chains
+    /// (see `WELL_KNOWN_CHAINS`) can be resolved this way -- there's no
+    /// source of additional chain ids beyond that fixed list.
+    pub async fn get_or_init_inquirer_by_chain_id(
+        &self,
+        chain_id: u64,
+    ) -> Option<Arc<EvmNodeInquirer>> {
+        if let Some(blockchain) = self.chain_index.read().await.get(&chain_id).cloned() {
+            return self.inquirers.read().await.get(&blockchain).cloned();
+        }
+        let blockchain = SupportedBlockchain::from_chain_id(chain_id)?;
+        Some(self.get_or_init_inquirer(blockchain).await)
+    }
+
+    /// Returns the last known node status for every chain with an
+    /// initialized inquirer, keyed by blockchain name.
+    pub async fn health_snapshot(&self) -> HashMap<String, Vec<NodeStatus>> {
+        let mut snapshot = HashMap::new();
+        for (blockchain, inquirer) in self.inquirers.read().await.iter() {
+            snapshot.insert(blockchain.as_str().to_string(), inquirer.health_snapshot().await);
         }
+        snapshot
+    }
+}
 
-        let mut inquirers = self.inquirers.write().await;
-        inquirers.entry(blockchain).or_insert(new_inquirer).clone()
+/// Background loop that periodically probes `inquirer`'s nodes for liveness,
+/// latency, and chain head, so `call_with_failover` always prefers a node
+/// that's actually synced. Mirrors graph-node's block-ingestor: a
+/// continuously running component that tracks the chain head per endpoint,
+/// adapted here to rotki's pull-based inquirer.
+async fn health_monitor_loop(inquirer: Arc<EvmNodeInquirer>) {
+    let mut ticker = time::interval(HEALTH_PROBE_INTERVAL);
+    loop {
+        ticker.tick().await;
+        inquirer.probe_all_nodes().await;
     }
 }
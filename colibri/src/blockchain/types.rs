@@ -1,6 +1,6 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// Represents supported EVM-compatible blockchains
-/// 
+///
 /// Note: This implementation only includes EVM chains and is not identical to Python's implementation
 /// which may include additional non-EVM blockchains.
 pub enum SupportedBlockchain {
@@ -14,7 +14,7 @@ pub enum SupportedBlockchain {
 }
 
 impl SupportedBlockchain {
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Self::Ethereum => "ETH",
             Self::Optimism => "OPTIMISM",
@@ -26,6 +26,22 @@ impl SupportedBlockchain {
         }
     }
 
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            Self::Ethereum => 1,
+            Self::Optimism => 10,
+            Self::ArbitrumOne => 42161,
+            Self::Base => 8453,
+            Self::BinanceSc => 56,
+            Self::Gnosis => 100,
+            Self::PolygonPos => 137,
+        }
+    }
+
+    /// Maps a numeric chain id to a well-known variant. Returns `None` for
+    /// chains outside this fixed list -- `default_rpc_nodes` has no
+    /// `chain_id` column of its own, so a chain beyond this list can't be
+    /// discovered from globaldb at all, only hardcoded here.
     pub fn from_chain_id(chain_id: u64) -> Option<Self> {
         // Maximum chain ID value is floor(MAX_UINT64 / 2) - 36 as per EIP-2294
         // https://github.com/ethereum/EIPs/blob/master/EIPS/eip-2294.md
@@ -42,10 +58,36 @@ impl SupportedBlockchain {
     }
 }
 
-/// Information about an RPC node
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Information about an RPC node.
+///
+/// `weight` and `owned` only affect the order `EvmNodeInquirer::call_with_failover`
+/// tries nodes in, so they're excluded from equality/hashing -- a node's identity
+/// is its `(name, endpoint, blockchain)`, not its current scoring metadata.
+#[derive(Debug, Clone)]
 pub struct RpcNode {
     pub name: String,
     pub endpoint: String,
     pub blockchain: SupportedBlockchain,
+    /// Relative priority among public (non-owned) nodes, treated as a weight
+    /// in a weighted-random ordering. Irrelevant for owned nodes, which are
+    /// always tried first.
+    pub weight: f64,
+    /// Whether this is a node we run ourselves, as opposed to a public one.
+    pub owned: bool,
+}
+
+impl PartialEq for RpcNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.endpoint == other.endpoint && self.blockchain == other.blockchain
+    }
+}
+
+impl Eq for RpcNode {}
+
+impl std::hash::Hash for RpcNode {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.endpoint.hash(state);
+        self.blockchain.hash(state);
+    }
 }
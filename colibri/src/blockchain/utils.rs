@@ -1,9 +1,77 @@
 use alloy::primitives::Address;
+use std::str::FromStr;
+
+use crate::types::ChainID;
+
+/// The namespace half of a CAIP-2 chain id (`namespace ":" reference`).
+/// `Eip155` and `Solana` are special-cased because their asset identifiers
+/// need chain-specific validation (a checksummed EVM address, a base58
+/// Solana address); every other namespace we recognize here only needs its
+/// *reference* validated, since the asset address itself round-trips
+/// through `AssetAddress::Other`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainNamespace {
+    Eip155,
+    Solana,
+    /// Bitcoin and other UTXO chains (CAIP-2 `bip122`). The reference is a
+    /// 32-hex-char prefix of the chain's genesis block hash.
+    Bip122,
+    /// Cosmos SDK chains (CAIP-2 `cosmos`), e.g. `cosmoshub-3`.
+    Cosmos,
+    /// Polkadot/Substrate chains (CAIP-2 `polkadot`), identified by a
+    /// genesis-hash-derived reference.
+    Polkadot,
+    /// Any other CAIP-2 namespace matching `[-a-z0-9]{3,8}` that we don't
+    /// have dedicated handling for yet.
+    Other(String),
+}
+
+impl ChainNamespace {
+    fn parse(namespace: &str) -> Option<Self> {
+        if !is_caip_namespace(namespace) {
+            return None;
+        }
+        Some(match namespace {
+            "eip155" => Self::Eip155,
+            "solana" => Self::Solana,
+            "bip122" => Self::Bip122,
+            "cosmos" => Self::Cosmos,
+            "polkadot" => Self::Polkadot,
+            other => Self::Other(other.to_string()),
+        })
+    }
+}
+
+/// A CAIP-2 chain id: `namespace ":" reference`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainId {
+    pub namespace: ChainNamespace,
+    pub reference: String,
+}
+
+impl ChainId {
+    /// The numeric chain id used for cache keys and chain lookups throughout
+    /// the icon pipeline, for the namespaces that have one: EIP-155's own
+    /// chain id, and Solana mainnet's pseudo-chain-id as used by smoldapp.
+    /// `None` for namespaces with no such numeric identity (a Cosmos chain
+    /// name, a Bitcoin genesis-block prefix, ...).
+    pub fn numeric_id(&self) -> Option<u64> {
+        match self.namespace {
+            ChainNamespace::Eip155 => self.reference.parse().ok(),
+            ChainNamespace::Solana => Some(SOLANA_CHAIN_ID),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AssetAddress {
     Evm(Address),
     Solana(String),
+    /// The CAIP-19 `asset_namespace`/`asset_reference` of a chain we don't
+    /// have dedicated validation for, kept opaque (e.g. a Cosmos denom, a
+    /// Bitcoin UTXO descriptor, a `slip44` coin type).
+    Other { namespace: String, address: String },
 }
 
 impl AssetAddress {
@@ -12,58 +80,158 @@ impl AssetAddress {
         match self {
             AssetAddress::Evm(address) => address.to_string().to_ascii_lowercase(),
             AssetAddress::Solana(address) => address.clone(),
+            AssetAddress::Other { address, .. } => address.clone(),
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct AssetIdentifier {
-    pub chain_id: u64,
+    pub chain: ChainId,
     pub contract_address: AssetAddress,
     pub token_id: Option<String>,
 }
 
+impl AssetIdentifier {
+    /// Shorthand for `self.chain.numeric_id()`, since most callers only care
+    /// about the EVM-style chain id and not the general CAIP-2 chain.
+    pub fn chain_id(&self) -> Option<u64> {
+        self.chain.numeric_id()
+    }
+}
+
 // Solana mainnet chain ID as used by smoldapp
 const SOLANA_CHAIN_ID: u64 = 1151111081099710;
 const SOLANA_ADDRESS_MIN_LENGTH: usize = 32;
 const SOLANA_ADDRESS_MAX_LENGTH: usize = 44;
 
-/// Parses an asset identifier supporting both EVM and Solana formats:
-/// - EVM: "eip155:{chain_id}/{asset_type}:{contract_address}[/{token_id}]"
-/// - Solana: "solana/{asset_type}:{contract_address}"
+const BIP122_REFERENCE_LENGTH: usize = 32;
+
+fn is_caip_namespace(s: &str) -> bool {
+    (3..=8).contains(&s.len())
+        && s.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+fn is_caip_chain_reference(s: &str) -> bool {
+    (1..=32).contains(&s.len())
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn is_caip_asset_reference(s: &str) -> bool {
+    (1..=128).contains(&s.len())
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '%')
+}
+
+fn is_bip122_reference(s: &str) -> bool {
+    s.len() == BIP122_REFERENCE_LENGTH && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Parses a CAIP-2 chain id (`namespace[:reference]`). The reference is
+/// optional only for the legacy bare `"solana"` form this parser has always
+/// accepted; every other namespace requires one.
+fn parse_chain_id(chain_part: &str) -> Option<ChainId> {
+    let (namespace_str, reference) = match chain_part.split_once(':') {
+        Some((namespace, reference)) => (namespace, reference.to_string()),
+        None if chain_part == "solana" => ("solana", String::new()),
+        None => return None,
+    };
+
+    let namespace = ChainNamespace::parse(namespace_str)?;
+
+    match namespace {
+        ChainNamespace::Eip155 => {
+            reference.parse::<u64>().ok()?;
+        }
+        ChainNamespace::Solana => {
+            if !reference.is_empty() && !is_caip_chain_reference(&reference) {
+                return None;
+            }
+        }
+        ChainNamespace::Bip122 => {
+            if !is_bip122_reference(&reference) {
+                return None;
+            }
+        }
+        ChainNamespace::Cosmos | ChainNamespace::Polkadot | ChainNamespace::Other(_) => {
+            if !is_caip_chain_reference(&reference) {
+                return None;
+            }
+        }
+    }
+
+    Some(ChainId { namespace, reference })
+}
+
+/// Parses a CAIP-19 asset identifier:
+/// `chain_id "/" asset_namespace ":" asset_reference [ "/" token_id ]`,
+/// where `chain_id` is itself a CAIP-2 id (`namespace ":" reference`).
+///
+/// `eip155` and `solana` chains get dedicated validation (a checksummed EVM
+/// address, a base58 Solana address); every other recognized namespace
+/// (`bip122`, `cosmos`, `polkadot`, and any other CAIP-2 namespace) round-trips
+/// its `asset_namespace`/`asset_reference` through `AssetAddress::Other`.
 ///
 /// Examples:
 ///   - "eip155:1/erc20:0x6B175474E89094C44Da98b954EedeAC495271d0F" (ERC-20 token)
 ///   - "eip155:1/erc721:0xBC4CA0EdA7647A8aB7C2061c2E118A18a936f13D/1" (ERC-721 NFT with token ID)
 ///   - "solana/token:So11111111111111111111111111111111111111112" (Solana SPL token)
-///   - "solana/nft:7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU" (Solana NFT)
+///   - "bip122:000000000019d6689c085ae165831e93/slip44:0" (Bitcoin, via slip44)
+///   - "cosmos:cosmoshub-3/slip44:118" (Cosmos Hub's native ATOM)
 ///
-/// Returns None if the format is invalid or any required component is missing.
+/// Returns None if the format is invalid or any component violates its
+/// CAIP-2/CAIP-19 pattern.
 pub fn parse_asset_identifier(identifier: &str) -> Option<AssetIdentifier> {
+    // EIP-3770 human-readable chain-prefixed addresses (e.g. "eth:0x...")
+    // look like a CAIP chain id but aren't one -- dispatch to that parser
+    // whenever the segment before the first ':' is a registered short name.
+    if let Some((short_name, _)) = identifier.split_once(':') {
+        if ChainID::from_short_name(short_name).is_some() {
+            return parse_eip3770(identifier);
+        }
+    }
+
     let parts: Vec<&str> = identifier.split('/').collect();
     if parts.len() < 2 {
         return None;
     }
 
-    let blockchain_part = parts[0];
-    if blockchain_part.starts_with("eip155:") {
-        parse_evm_identifier(&parts)
-    } else if blockchain_part == "solana" {
-        parse_solana_identifier(&parts)
-    } else {
-        None
+    let chain = parse_chain_id(parts[0])?;
+
+    match chain.namespace {
+        ChainNamespace::Eip155 => parse_evm_identifier(chain, &parts),
+        ChainNamespace::Solana => parse_solana_identifier(chain, &parts),
+        _ => parse_generic_identifier(chain, &parts),
     }
 }
 
-/// Parse EVM (EIP-155) asset identifier
-fn parse_evm_identifier(parts: &[&str]) -> Option<AssetIdentifier> {
-    // Extract chain ID from "eip155:1" format
-    let chain_parts: Vec<&str> = parts[0].splitn(2, ':').collect();
-    debug_assert_eq!(chain_parts.len(), 2);
-    debug_assert_eq!(chain_parts[0], "eip155");
+/// Parses an EIP-3770 chain-prefixed address (`shortName ":" address`), e.g.
+/// `eth:0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045`. Accepts both checksummed
+/// and plain lowercase/uppercase hex, falling back to `Address::from_str`
+/// when `parse_checksummed` rejects the input. Only covers EVM chains, since
+/// EIP-3770 short names are specifically a registry of EIP-155 chain ids.
+fn parse_eip3770(identifier: &str) -> Option<AssetIdentifier> {
+    let (short_name, address_str) = identifier.split_once(':')?;
+    let chain_id = ChainID::from_short_name(short_name)?;
+    if address_str.is_empty() {
+        return None;
+    }
 
-    let chain_id = chain_parts[1].parse::<u64>().ok()?;
+    let contract_address = Address::parse_checksummed(address_str, None)
+        .or_else(|_| Address::from_str(address_str))
+        .ok()?;
 
+    Some(AssetIdentifier {
+        chain: ChainId {
+            namespace: ChainNamespace::Eip155,
+            reference: chain_id.id().to_string(),
+        },
+        contract_address: AssetAddress::Evm(contract_address),
+        token_id: None,
+    })
+}
+
+/// Parse EVM (EIP-155) asset identifier
+fn parse_evm_identifier(chain: ChainId, parts: &[&str]) -> Option<AssetIdentifier> {
     // Parse asset type and contract address from "erc20:0x..." format
     let asset_parts: Vec<&str> = parts[1].splitn(2, ':').collect();
     if asset_parts.len() != 2 {
@@ -79,14 +247,14 @@ fn parse_evm_identifier(parts: &[&str]) -> Option<AssetIdentifier> {
     let token_id = parts.get(2).map(|s| s.to_string());
 
     Some(AssetIdentifier {
-        chain_id,
+        chain,
         contract_address: AssetAddress::Evm(contract_address),
         token_id,
     })
 }
 
 /// Parse Solana asset identifier
-fn parse_solana_identifier(parts: &[&str]) -> Option<AssetIdentifier> {
+fn parse_solana_identifier(chain: ChainId, parts: &[&str]) -> Option<AssetIdentifier> {
     // Parse asset type and contract address from "token:So11..." format
     let asset_parts: Vec<&str> = parts[1].splitn(2, ':').collect();
     if asset_parts.len() != 2 {
@@ -106,12 +274,33 @@ fn parse_solana_identifier(parts: &[&str]) -> Option<AssetIdentifier> {
     }
 
     Some(AssetIdentifier {
-        chain_id: SOLANA_CHAIN_ID,
+        chain,
         contract_address: AssetAddress::Solana(contract_address.to_string()),
         token_id: None, // we don't need it for solana
     })
 }
 
+/// Parse the asset identifier of any chain namespace without dedicated
+/// handling (bip122, cosmos, polkadot, ...), round-tripping its
+/// `asset_namespace`/`asset_reference` through `AssetAddress::Other`.
+fn parse_generic_identifier(chain: ChainId, parts: &[&str]) -> Option<AssetIdentifier> {
+    let (namespace, reference) = parts[1].split_once(':')?;
+    if !is_caip_namespace(namespace) || !is_caip_asset_reference(reference) {
+        return None;
+    }
+
+    let token_id = parts.get(2).map(|s| s.to_string());
+
+    Some(AssetIdentifier {
+        chain,
+        contract_address: AssetAddress::Other {
+            namespace: namespace.to_string(),
+            address: reference.to_string(),
+        },
+        token_id,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,7 +311,10 @@ mod tests {
         // Test ERC-20 format
         let erc20 = "eip155:1/erc20:0x6B175474E89094C44Da98b954EedeAC495271d0F";
         let expected_erc20 = AssetIdentifier {
-            chain_id: 1,
+            chain: ChainId {
+                namespace: ChainNamespace::Eip155,
+                reference: "1".to_string(),
+            },
             contract_address: AssetAddress::Evm(address!(
                 "0x6B175474E89094C44Da98b954EedeAC495271d0F"
             )),
@@ -133,7 +325,10 @@ mod tests {
         // Test ERC-721 format
         let erc721 = "eip155:1/erc721:0xBC4CA0EdA7647A8aB7C2061c2E118A18a936f13D/1";
         let expected_erc721 = AssetIdentifier {
-            chain_id: 1,
+            chain: ChainId {
+                namespace: ChainNamespace::Eip155,
+                reference: "1".to_string(),
+            },
             contract_address: AssetAddress::Evm(address!(
                 "0xBC4CA0EdA7647A8aB7C2061c2E118A18a936f13D"
             )),
@@ -144,7 +339,10 @@ mod tests {
         // Test Solana token format
         let solana_token = "solana/token:So11111111111111111111111111111111111111112";
         let expected_solana_token = AssetIdentifier {
-            chain_id: SOLANA_CHAIN_ID,
+            chain: ChainId {
+                namespace: ChainNamespace::Solana,
+                reference: String::new(),
+            },
             contract_address: AssetAddress::Solana(
                 "So11111111111111111111111111111111111111112".to_string(),
             ),
@@ -154,11 +352,18 @@ mod tests {
             parse_asset_identifier(solana_token),
             Some(expected_solana_token)
         );
+        assert_eq!(
+            parse_asset_identifier(solana_token).unwrap().chain_id(),
+            Some(SOLANA_CHAIN_ID)
+        );
 
         // Test Solana NFT format
         let solana_nft = "solana/nft:7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
         let expected_solana_nft = AssetIdentifier {
-            chain_id: SOLANA_CHAIN_ID,
+            chain: ChainId {
+                namespace: ChainNamespace::Solana,
+                reference: String::new(),
+            },
             contract_address: AssetAddress::Solana(
                 "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU".to_string(),
             ),
@@ -168,6 +373,63 @@ mod tests {
             parse_asset_identifier(solana_nft),
             Some(expected_solana_nft)
         );
+
+        // Bitcoin via bip122, native asset referenced through slip44
+        let bitcoin = "bip122:000000000019d6689c085ae165831e93/slip44:0";
+        let parsed = parse_asset_identifier(bitcoin).unwrap();
+        assert_eq!(parsed.chain.namespace, ChainNamespace::Bip122);
+        assert_eq!(parsed.chain_id(), None);
+        assert_eq!(
+            parsed.contract_address,
+            AssetAddress::Other {
+                namespace: "slip44".to_string(),
+                address: "0".to_string(),
+            }
+        );
+
+        // Cosmos Hub, native asset referenced through slip44
+        let cosmos = "cosmos:cosmoshub-3/slip44:118";
+        let parsed = parse_asset_identifier(cosmos).unwrap();
+        assert_eq!(parsed.chain.namespace, ChainNamespace::Cosmos);
+        assert_eq!(parsed.chain.reference, "cosmoshub-3");
+
+        // Polkadot relay chain, native asset referenced through slip44
+        let polkadot = "polkadot:91b171bb158e2d3848fa23a9f1c25182/slip44:354";
+        let parsed = parse_asset_identifier(polkadot).unwrap();
+        assert_eq!(parsed.chain.namespace, ChainNamespace::Polkadot);
+    }
+
+    #[test]
+    fn test_parse_eip3770_identifier() {
+        // Checksummed address, short name for Ethereum mainnet
+        let checksummed = "eth:0x6B175474E89094C44Da98b954EedeAC495271d0F";
+        let parsed = parse_asset_identifier(checksummed).unwrap();
+        assert_eq!(parsed.chain_id(), Some(1));
+        assert_eq!(
+            parsed.contract_address,
+            AssetAddress::Evm(address!("0x6B175474E89094C44Da98b954EedeAC495271d0F"))
+        );
+
+        // Lowercase (non-checksummed) address falls back to Address::from_str
+        let lowercase = "arb1:0x6b175474e89094c44da98b954eedeac495271d0f";
+        let parsed = parse_asset_identifier(lowercase).unwrap();
+        assert_eq!(parsed.chain_id(), Some(42161));
+        assert_eq!(
+            parsed.contract_address,
+            AssetAddress::Evm(address!("0x6B175474E89094C44Da98b954EedeAC495271d0F"))
+        );
+
+        // Unknown short name isn't mistaken for a CAIP namespace
+        assert_eq!(
+            parse_asset_identifier("notachain:0x6B175474E89094C44Da98b954EedeAC495271d0F"),
+            None
+        );
+
+        // Missing address
+        assert_eq!(parse_asset_identifier("eth:"), None);
+
+        // Malformed address
+        assert_eq!(parse_asset_identifier("eth:not-an-address"), None);
     }
 
     #[test]
@@ -230,5 +492,21 @@ mod tests {
 
         // Missing Solana address
         assert_eq!(parse_asset_identifier("solana/token:"), None);
+
+        // bip122 reference not a 32-hex-char genesis-block prefix
+        assert_eq!(
+            parse_asset_identifier("bip122:not-hex/slip44:0"),
+            None
+        );
+
+        // Chain namespace too short
+        assert_eq!(parse_asset_identifier("ab:1/slip44:0"), None);
+
+        // Generic asset reference violates its length bound
+        let too_long = "a".repeat(129);
+        assert_eq!(
+            parse_asset_identifier(&format!("cosmos:cosmoshub-3/slip44:{}", too_long)),
+            None
+        );
     }
 }
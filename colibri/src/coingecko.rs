@@ -1,12 +1,58 @@
 use axum::body::Bytes;
 use log::{debug, error};
+use rand::Rng;
+use reqwest::{header, StatusCode};
 use serde_json::Value;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub const COINGECKO_BASE_URL: &str = "https://api.coingecko.com";
 
 use crate::globaldb;
 
+/// How long a cached image is served without revalidation.
+const IMAGE_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Retries a rate-limited request gets after its first attempt before giving
+/// up and falling back to whatever's cached (or `None`).
+const MAX_RETRY_ATTEMPTS: u32 = 4;
+const RETRY_BASE_BACKOFF: Duration = Duration::from_millis(500);
+const RETRY_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// The image sizes CoinGecko's `/coins/{id}` response exposes under `image`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSize {
+    Thumb,
+    Small,
+    Large,
+}
+
+impl ImageSize {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImageSize::Thumb => "thumb",
+            ImageSize::Small => "small",
+            ImageSize::Large => "large",
+        }
+    }
+}
+
+/// An image cached from a previous `query_asset_image` call.
+struct CachedImage {
+    bytes: Vec<u8>,
+    etag: Option<String>,
+    fetched_at: i64,
+}
+
+impl CachedImage {
+    fn is_fresh(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        now.saturating_sub(self.fetched_at) < IMAGE_CACHE_TTL.as_secs() as i64
+    }
+}
+
 pub struct Coingecko {
     client: reqwest::Client,
     globaldb: Arc<globaldb::GlobalDB>,
@@ -22,9 +68,12 @@ impl Coingecko {
         }
     }
 
-    /// Queries the asset image of the given asset id from coingecko
-    /// and returns its contents if found
-    pub async fn query_asset_image(&self, asset_id: &str) -> Option<Bytes> {
+    /// Queries the asset image of the given asset id and size from
+    /// coingecko, serving a cached copy when it's still within
+    /// `IMAGE_CACHE_TTL` and revalidating with `If-None-Match` otherwise.
+    /// Falls back to the last cached bytes (if any) on error, including
+    /// after exhausting `MAX_RETRY_ATTEMPTS` on a `429`.
+    pub async fn query_asset_image(&self, asset_id: &str, size: ImageSize) -> Option<Bytes> {
         let coingecko_id = match self.globaldb.get_coingecko_id(asset_id).await {
             Err(e) => {
                 error!("Failed to get coingecko id for {} due to {}", asset_id, e);
@@ -32,6 +81,25 @@ impl Coingecko {
             }
             Ok(identifier) => identifier?,
         };
+
+        let cached = self
+            .globaldb
+            .get_coingecko_image_cache(asset_id, size.as_str())
+            .await
+            .ok()
+            .flatten()
+            .map(|(bytes, etag, fetched_at)| CachedImage {
+                bytes,
+                etag,
+                fetched_at,
+            });
+
+        if let Some(cached) = &cached {
+            if cached.is_fresh() {
+                return Some(Bytes::from(cached.bytes.clone()));
+            }
+        }
+
         let url = format!("{}/api/v3/coins/{}", self.base_url, coingecko_id);
         let params = [
             ("localization", "false"),
@@ -42,24 +110,137 @@ impl Coingecko {
             ("sparkline", "false"),
         ];
 
-        match self.client.get(&url).query(&params).send().await {
-            Ok(response) => {
-                if response.status().is_success() {
-                    if let Ok(data) = response.json::<Value>().await {
-                        if let Some(image_url) = data["image"]["small"].as_str() {
-                            if let Ok(image_response) = self.client.get(image_url).send().await {
-                                return image_response.bytes().await.ok();
-                            }
-                        }
-                    }
+        let response = match self
+            .send_with_retry(|| self.client.get(&url).query(&params))
+            .await
+        {
+            Some(response) if response.status().is_success() => response,
+            _ => {
+                debug!("Icon not found in coingecko for {}", asset_id);
+                return cached.map(|c| Bytes::from(c.bytes));
+            }
+        };
+
+        let image_url = match response.json::<Value>().await {
+            Ok(data) => data["image"][size.as_str()].as_str().map(str::to_string),
+            Err(_) => None,
+        };
+        let Some(image_url) = image_url else {
+            return cached.map(|c| Bytes::from(c.bytes));
+        };
+
+        let etag = cached.as_ref().and_then(|c| c.etag.clone());
+        let image_response = match self
+            .send_with_retry(|| {
+                let request = self.client.get(&image_url);
+                match &etag {
+                    Some(etag) => request.header(header::IF_NONE_MATCH, etag.clone()),
+                    None => request,
                 }
+            })
+            .await
+        {
+            Some(response) => response,
+            None => return cached.map(|c| Bytes::from(c.bytes)),
+        };
+
+        if image_response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                let _ = self
+                    .globaldb
+                    .store_coingecko_image_cache(
+                        asset_id,
+                        size.as_str(),
+                        &cached.bytes,
+                        cached.etag.as_deref(),
+                    )
+                    .await;
+                return Some(Bytes::from(cached.bytes));
             }
-            Err(e) => error!("Failed to query coingecko for {} due to {}", asset_id, e),
         }
 
-        debug!("Icon not found in coingecko for {}", asset_id);
-        None
+        if !image_response.status().is_success() {
+            return cached.map(|c| Bytes::from(c.bytes));
+        }
+
+        let new_etag = image_response
+            .headers()
+            .get(header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let bytes = match image_response.bytes().await {
+            Ok(bytes) => bytes,
+            Err(_) => return cached.map(|c| Bytes::from(c.bytes)),
+        };
+
+        let _ = self
+            .globaldb
+            .store_coingecko_image_cache(asset_id, size.as_str(), &bytes, new_etag.as_deref())
+            .await;
+
+        Some(bytes)
     }
+
+    /// Builds and sends a request via `make_request`, transparently retrying
+    /// on `429` with exponential backoff (honoring `Retry-After` when
+    /// present) up to `MAX_RETRY_ATTEMPTS` times. Returns `None` once the
+    /// transport errors or the retry budget is exhausted.
+    async fn send_with_retry<F>(&self, mut make_request: F) -> Option<reqwest::Response>
+    where
+        F: FnMut() -> reqwest::RequestBuilder,
+    {
+        let mut attempt = 0;
+        loop {
+            let response = match make_request().send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    error!("coingecko request failed: {}", e);
+                    return None;
+                }
+            };
+
+            if response.status() != StatusCode::TOO_MANY_REQUESTS {
+                return Some(response);
+            }
+
+            attempt += 1;
+            if attempt > MAX_RETRY_ATTEMPTS {
+                debug!("coingecko rate limit exceeded after {} retries", attempt - 1);
+                return None;
+            }
+
+            let wait = retry_after(&response).unwrap_or_else(|| backoff_with_jitter(attempt));
+            debug!(
+                "coingecko rate limited, retrying in {:?} (attempt {}/{})",
+                wait, attempt, MAX_RETRY_ATTEMPTS
+            );
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Parses a numeric `Retry-After` header (CoinGecko doesn't send the
+/// HTTP-date form), if present.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Exponential backoff capped at `RETRY_MAX_BACKOFF`, with up to 50% jitter
+/// added so many callers rate-limited at once don't all retry in lockstep.
+fn backoff_with_jitter(attempt: u32) -> Duration {
+    let backoff = RETRY_BASE_BACKOFF
+        .checked_mul(1u32 << attempt.min(8))
+        .unwrap_or(RETRY_MAX_BACKOFF)
+        .min(RETRY_MAX_BACKOFF);
+    let jitter_ms = rand::rng().random_range(0..=(backoff.as_millis() as u64 / 2).max(1));
+    backoff + Duration::from_millis(jitter_ms)
 }
 
 #[cfg(test)]
@@ -68,7 +249,7 @@ mod test {
     use axum::body::Bytes;
     use std::sync::Arc;
 
-    use super::Coingecko;
+    use super::{Coingecko, ImageSize};
 
     #[tokio::test]
     async fn test_coingecko_query() {
@@ -95,7 +276,13 @@ mod test {
             .create();
 
         assert_eq!(
-            coingecko.query_asset_image("ETH").await,
+            coingecko.query_asset_image("ETH", ImageSize::Small).await,
+            Some(Bytes::from_static(b"Image bytes")),
+        );
+
+        // Second call should be served from the cache without hitting the mocks again
+        assert_eq!(
+            coingecko.query_asset_image("ETH", ImageSize::Small).await,
             Some(Bytes::from_static(b"Image bytes")),
         );
     }
@@ -0,0 +1,109 @@
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio::time;
+
+use crate::database::user_db::DBHandler;
+
+const SESSION_TOKEN_BYTES: usize = 32;
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+const EVICTION_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+struct Session {
+    handler: DBHandler,
+    last_active: Instant,
+}
+
+/// Holds every user database currently unlocked in this process, keyed by an
+/// opaque session token handed back from `unlock_user`, instead of the single
+/// shared connection the API used to keep in `AppState`. This lets more than
+/// one user be unlocked at the same time and requires a caller to prove it
+/// unlocked a database (by presenting the token) before touching it.
+pub struct SessionStore {
+    sessions: RwLock<HashMap<String, Session>>,
+    idle_timeout: Duration,
+}
+
+impl SessionStore {
+    pub fn new() -> Self {
+        Self::with_idle_timeout(DEFAULT_IDLE_TIMEOUT)
+    }
+
+    pub fn with_idle_timeout(idle_timeout: Duration) -> Self {
+        Self {
+            sessions: RwLock::new(HashMap::new()),
+            idle_timeout,
+        }
+    }
+
+    /// Registers a newly-unlocked database under a fresh, cryptographically
+    /// random token and returns it.
+    pub async fn create(&self, handler: DBHandler) -> String {
+        let token = generate_token();
+        self.sessions.write().await.insert(
+            token.clone(),
+            Session {
+                handler,
+                last_active: Instant::now(),
+            },
+        );
+        token
+    }
+
+    /// Resolves `token` to the database handler it was issued for, refreshing
+    /// its idle timer. Returns `None` if the token is unknown, was never
+    /// issued, or has since been evicted.
+    pub async fn get(&self, token: &str) -> Option<DBHandler> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.get_mut(token)?;
+        session.last_active = Instant::now();
+        Some(session.handler.clone())
+    }
+
+    /// Removes the session for `token`, e.g. on logout, closing its database
+    /// connection. Returns the handler that was removed, if any.
+    pub async fn remove(&self, token: &str) -> Option<DBHandler> {
+        let handler = self.sessions.write().await.remove(token).map(|s| s.handler);
+        if let Some(handler) = &handler {
+            close(handler);
+        }
+        handler
+    }
+
+    /// Evicts and closes every session that's been idle longer than
+    /// `idle_timeout`.
+    async fn evict_idle(&self) {
+        let mut sessions = self.sessions.write().await;
+        sessions.retain(|_, session| {
+            let expired = session.last_active.elapsed() >= self.idle_timeout;
+            if expired {
+                close(&session.handler);
+            }
+            !expired
+        });
+    }
+}
+
+fn close(handler: &DBHandler) {
+    if let Some(client) = &handler.client {
+        std::mem::drop(client.close());
+    }
+}
+
+fn generate_token() -> String {
+    let mut bytes = [0u8; SESSION_TOKEN_BYTES];
+    rand::rng().fill(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Periodically evicts idle sessions from `store`. Spawned once at startup,
+/// mirroring `blockchain::node_inquirer`'s health-monitor loop.
+pub async fn run_idle_eviction(store: Arc<SessionStore>) {
+    let mut ticker = time::interval(EVICTION_CHECK_INTERVAL);
+    loop {
+        ticker.tick().await;
+        store.evict_idle().await;
+    }
+}
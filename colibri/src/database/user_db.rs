@@ -92,8 +92,14 @@ impl DBHandler {
         }
     }
 
-    // Get NFT mappings from the user database
-    pub async fn get_nft_mappings(&self, identifiers: Vec<String>) -> Result<HashMap<String, NftData>, DBError> {
+    // Get NFT mappings from the user database, enriching any identifier
+    // whose row is missing or has NULL collection/image metadata by
+    // querying `nft_metadata` and persisting the result for next time.
+    pub async fn get_nft_mappings(
+        &self,
+        identifiers: Vec<String>,
+        nft_metadata: &crate::nft_metadata::NftMetadata,
+    ) -> Result<HashMap<String, NftData>, DBError> {
         if identifiers.is_empty() {
             return Ok(HashMap::new());
         }
@@ -103,41 +109,94 @@ impl DBHandler {
             None => return Err(DBError::UnlockError("No client found".to_string())),
         };
 
-        match client
-            .conn(move |conn| {
-                let mut nft_mappings = HashMap::new();
-                let params = std::iter::repeat_n("?", identifiers.len())
-                    .collect::<Vec<_>>()
-                    .join(",");
+        let (mut nft_mappings, needs_enrichment) = {
+            let identifiers = identifiers.clone();
+            client
+                .conn(move |conn| {
+                    let mut nft_mappings = HashMap::new();
+                    let mut needs_enrichment = Vec::new();
+                    let params = std::iter::repeat_n("?", identifiers.len())
+                        .collect::<Vec<_>>()
+                        .join(",");
+
+                    let query = format!(
+                        "SELECT identifier, name, collection_name, image_url FROM nfts WHERE identifier IN ({})",
+                        params
+                    );
 
-                let query = format!(
-                    "SELECT identifier, name, collection_name, image_url FROM nfts WHERE identifier IN ({})",
-                    params
-                );
+                    let mut stmt = conn.prepare(&query)?;
+                    let mut rows = stmt.query(rusqlite::params_from_iter(identifiers.iter()))?;
+
+                    while let Some(row) = rows.next()? {
+                        let identifier: String = row.get(0)?;
+                        let collection_name: Option<String> = row.get(2)?;
+                        let image_url: Option<String> = row.get(3)?;
+
+                        if collection_name.is_none() || image_url.is_none() {
+                            needs_enrichment.push(identifier.clone());
+                        }
+
+                        nft_mappings.insert(
+                            identifier,
+                            NftData {
+                                name: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                                asset_type: "nft".to_string(),
+                                collection_name,
+                                image_url,
+                            },
+                        );
+                    }
+
+                    for identifier in &identifiers {
+                        if !nft_mappings.contains_key(identifier) {
+                            needs_enrichment.push(identifier.clone());
+                        }
+                    }
+
+                    Ok((nft_mappings, needs_enrichment))
+                })
+                .await
+                .map_err(|e| DBError::QueryError(e.to_string()))?
+        };
 
-                let mut stmt = conn.prepare(&query)?;
-                let mut rows = stmt.query(rusqlite::params_from_iter(identifiers.iter()))?;
+        for identifier in needs_enrichment {
+            if let Some(data) = nft_metadata.fetch(&identifier).await {
+                self.persist_nft_metadata(&identifier, &data).await?;
+                nft_mappings.insert(identifier, data);
+            }
+        }
 
-                while let Some(row) = rows.next()? {
-                    let identifier: String = row.get(0)?;
-
-                    nft_mappings.insert(
-                        identifier,
-                        NftData {
-                            name: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
-                            asset_type: "nft".to_string(),
-                            collection_name: row.get(2)?,
-                            image_url: row.get(3)?,
-                        },
-                    );
-                }
-                Ok(nft_mappings)
+        Ok(nft_mappings)
+    }
+
+    /// Writes freshly fetched metadata back into an existing `nfts` row so
+    /// subsequent lookups are served locally. Only updates rows the Python
+    /// backend already created -- we don't know enough to satisfy that
+    /// table's other NOT NULL columns (last_price, owner_address, ...) for
+    /// a brand new row, so an identifier with no existing row at all is
+    /// still returned to the caller but not persisted.
+    async fn persist_nft_metadata(&self, identifier: &str, data: &NftData) -> Result<(), DBError> {
+        let client = match &self.client {
+            Some(client) => client,
+            None => return Err(DBError::UnlockError("No client found".to_string())),
+        };
+
+        let identifier = identifier.to_string();
+        let name = data.name.clone();
+        let collection_name = data.collection_name.clone();
+        let image_url = data.image_url.clone();
+
+        client
+            .conn(move |conn| {
+                conn.execute(
+                    "UPDATE nfts SET name = ?, collection_name = ?, image_url = ? WHERE identifier = ?",
+                    rusqlite::params![name, collection_name, image_url, identifier],
+                )
             })
             .await
-        {
-            Ok(nft_mappings) => Ok(nft_mappings),
-            Err(e) => Err(DBError::QueryError(e.to_string())),
-        }
+            .map_err(|e| DBError::QueryError(e.to_string()))?;
+
+        Ok(())
     }
 }
 
@@ -243,9 +302,14 @@ mod tests {
             }).await.unwrap();
         }
 
+        // The `_nft_...` identifiers below aren't CAIP-19 EVM NFT identifiers,
+        // so `NftMetadata::fetch` returns None for them without contacting
+        // this (unreachable) base url, and enrichment leaves NULLs as-is.
+        let nft_metadata = crate::nft_metadata::NftMetadata::new("http://127.0.0.1:0".to_string());
+
         // Query multiple existing NFTs
         let identifiers = vec!["_nft_0x123".to_string(), "_nft_0x456".to_string()];
-        let nft_mappings = db_handler.get_nft_mappings(identifiers).await.unwrap();
+        let nft_mappings = db_handler.get_nft_mappings(identifiers, &nft_metadata).await.unwrap();
 
         assert_eq!(nft_mappings.len(), 2);
 
@@ -255,9 +319,13 @@ mod tests {
         assert_eq!(nft1.collection_name, Some("Test Collection".to_string()));
         assert_eq!(nft1.image_url, Some("https://example.com/image.png".to_string()));
 
-        // NFT with NULL collection_name and image_url
+        // NFT with NULL collection_name and image_url: enrichment is attempted
+        // but yields nothing for this identifier, so the row is returned unchanged.
         let identifiers_with_nulls = vec!["_nft_0x789".to_string()];
-        let nft_mappings_nulls = db_handler.get_nft_mappings(identifiers_with_nulls).await.unwrap();
+        let nft_mappings_nulls = db_handler
+            .get_nft_mappings(identifiers_with_nulls, &nft_metadata)
+            .await
+            .unwrap();
 
         let nft3 = nft_mappings_nulls.get("_nft_0x789").unwrap();
         assert_eq!(nft3.name, "NFT without metadata");
@@ -265,12 +333,15 @@ mod tests {
         assert_eq!(nft3.image_url, None);
 
         // Empty input returns empty result
-        let empty_result = db_handler.get_nft_mappings(vec![]).await.unwrap();
+        let empty_result = db_handler.get_nft_mappings(vec![], &nft_metadata).await.unwrap();
         assert!(empty_result.is_empty());
 
-        // Non-existent NFT returns empty result
+        // Non-existent NFT: enrichment attempted, fails, nothing to return
         let non_existent = vec!["_nft_nonexistent".to_string()];
-        let empty_result = db_handler.get_nft_mappings(non_existent).await.unwrap();
+        let empty_result = db_handler
+            .get_nft_mappings(non_existent, &nft_metadata)
+            .await
+            .unwrap();
         assert!(empty_result.is_empty());
     }
 }
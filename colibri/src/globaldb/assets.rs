@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use serde::Serialize;
+use sqlx::Row;
 
 use crate::globaldb::errors::DBOutput;
 use crate::globaldb::GlobalDB;
@@ -65,40 +66,51 @@ impl GlobalDB {
     ) -> DBOutput<(HashMap<String, AssetMappings>, HashMap<String, CollectionInfo>)> {
         let mut collections: HashMap<String, CollectionInfo> = HashMap::new();
         let mut assets: HashMap<String, AssetMappings> = HashMap::new();
-        let params = std::iter::repeat_n("?", identifiers.len())
+
+        let placeholders = std::iter::repeat_n("?", identifiers.len())
             .collect::<Vec<_>>()
             .join(",");
+        let sql = format!(
+            "{} WHERE a.identifier IN ({})",
+            ALL_ASSETS_TABLES_QUERY_WITH_COLLECTIONS, placeholders,
+        );
+
+        // The IN-list has a variable arity, so unlike the rest of the
+        // globaldb queries (see `handler.rs`), this one can't go through
+        // `query!`/`query_as!` -- they need a SQL string fixed at compile
+        // time to check it against the schema. It's dispatched through
+        // plain `query` instead, validated against the live rows at
+        // runtime like before the rest of the module moved to sqlx.
+        let mut query = sqlx::query(&sql);
+        for identifier in identifiers {
+            query = query.bind(identifier);
+        }
+        let rows = query.fetch_all(self.sqlite_pool()?).await?;
 
-        let conn_guard = self.conn.lock().await;
-
-        let mut stmt = conn_guard.prepare(
-            format!(
-                "{} WHERE a.identifier IN ({})",
-                ALL_ASSETS_TABLES_QUERY_WITH_COLLECTIONS, params,
-            )
-            .as_str(),
-        )?;
-        let mut rows = stmt.query(rusqlite::params_from_iter(identifiers.iter()))?;
-        while let Some(row) = rows.next()? {
+        for row in &rows {
             // Pull out IDs once so we can reuse without re-reading columns
-            let collection_id: Option<u32> = row.get("collection_id")?;
-            let identifier: String = row.get("identifier")?;
+            let collection_id: Option<u32> = row.try_get("collection_id").unwrap_or_default();
+            // Unlike every other column here, a read failure on the primary
+            // key shouldn't degrade to an empty string -- that would key a
+            // bogus asset entry under "" and mask a real schema mismatch
+            // instead of surfacing it.
+            let identifier: String = row.try_get("identifier")?;
 
             // Insert the collection only once (keeps the first seen value)
             if let Some(id) = collection_id {
                 collections.entry(id.to_string()).or_insert_with(|| CollectionInfo {
-                    name: row.get("collection_name").unwrap_or_default(),
-                    symbol: row.get("collection_symbol").unwrap_or_default(),
-                    main_asset: row.get("main_asset").unwrap_or_default(),
+                    name: row.try_get("collection_name").unwrap_or_default(),
+                    symbol: row.try_get("collection_symbol").unwrap_or_default(),
+                    main_asset: row.try_get("main_asset").unwrap_or_default(),
                 });
             }
 
             // Insert the asset only once
             assets.entry(identifier).or_insert_with(|| {
-                let custom_type: Option<String> = row.get("custom_type").unwrap_or_default();
+                let custom_type: Option<String> = row.try_get("custom_type").unwrap_or_default();
                 let asset_type = if custom_type.is_some() {
                     "custom asset".to_string()
-                } else if let Ok(type_str) = row.get::<_, String>("type") {
+                } else if let Ok(type_str) = row.try_get::<String, _>("type") {
                     AssetType::deserialize_from_db(&type_str)
                         .map(|t| t.serialize())
                         .unwrap_or_else(|_| type_str)
@@ -106,23 +118,23 @@ impl GlobalDB {
                     String::new()
                 };
 
-                let evm_chain = row.get::<_, Option<u32>>("chain")
+                let evm_chain = row.try_get::<Option<u32>, _>("chain")
                     .unwrap_or_default()
-                    .and_then(|id| ChainID::deserialize_from_db(id).ok())
+                    .map(ChainID::deserialize_from_db)
                     .map(|chain| chain.to_name());
 
                 AssetMappings {
-                    name: row.get("name").unwrap_or_default(),
-                    symbol: row.get("symbol").unwrap_or_default(),
+                    name: row.try_get("name").unwrap_or_default(),
+                    symbol: row.try_get("symbol").unwrap_or_default(),
                     collection_id: collection_id.map(|id| id.to_string()),
                     asset_type,
                     evm_chain,
                     custom_asset_type: custom_type,
-                    is_spam: row.get::<_, Option<String>>("protocol")
+                    is_spam: row.try_get::<Option<String>, _>("protocol")
                         .unwrap_or_default()
                         .as_deref() == Some("spam"),
-                    coingecko: row.get("coingecko").unwrap_or_default(),
-                    cryptocompare: row.get("cryptocompare").unwrap_or_default(),
+                    coingecko: row.try_get("coingecko").unwrap_or_default(),
+                    cryptocompare: row.try_get("cryptocompare").unwrap_or_default(),
                 }
             });
         }
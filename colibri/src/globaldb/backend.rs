@@ -0,0 +1,352 @@
+use std::any::Any;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::{PgPool, Row, SqlitePool};
+
+use crate::blockchain::{parse_asset_identifier, RpcNode, SupportedBlockchain};
+use crate::globaldb::errors::DBOutput;
+use crate::globaldb::handler::CollectionVariant;
+
+/// Number of connections kept in a backend's pool. Queries dispatched to
+/// the pool run on whichever connection is free, so this is effectively
+/// the max number of globaldb reads that can run concurrently.
+const POOL_SIZE: u32 = 4;
+
+/// Backend-agnostic read access to the asset-data tables the Python
+/// backend owns (assets, RPC nodes, collections, ...). `GlobalDB` holds
+/// one of these, chosen by `new`/`new_with_backend_url` from the asset
+/// data source's URL scheme, so the rest of colibri never has to know
+/// whether that data lives in a local SQLite file or a shared Postgres
+/// instance.
+#[async_trait]
+pub(crate) trait AssetDataBackend: Send + Sync {
+    async fn get_coingecko_id(&self, asset_id: &str) -> DBOutput<Option<String>>;
+    async fn get_collection_main_asset(&self, asset_id: &str) -> DBOutput<Option<String>>;
+    async fn get_collection_variants(&self, asset_id: &str) -> DBOutput<Vec<CollectionVariant>>;
+    async fn get_rpc_nodes(&self, blockchain: SupportedBlockchain) -> DBOutput<Vec<RpcNode>>;
+    async fn get_assets_in_collection(&self, collection_id: u32) -> DBOutput<Vec<String>>;
+    async fn is_uniswap_position(&self, asset_id: &str) -> DBOutput<bool>;
+
+    /// Reads the `version` row the rotki python backend stores in the
+    /// `settings` table after each global-db upgrade, so `GlobalDB::new`
+    /// can refuse to run against a schema it predates instead of failing
+    /// with an opaque SQL error partway through some unrelated request.
+    async fn schema_version(&self) -> DBOutput<u32>;
+
+    /// Lets `GlobalDB` recover the concrete backend when a caller needs
+    /// something the trait doesn't expose yet (e.g. `assets.rs`'s
+    /// variable-arity "all assets" join, which only has a SQLite
+    /// implementation so far -- see `GlobalDB::sqlite_pool`).
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// Builds every `CollectionVariant` entry for `members`, tagging each with
+/// the EVM chain it was parsed from. Shared between backends since the
+/// logic is pure and doesn't depend on which database produced `members`.
+fn collection_variants_from_members(members: Vec<String>) -> Vec<CollectionVariant> {
+    members
+        .into_iter()
+        .filter_map(|identifier| {
+            let chain_id = parse_asset_identifier(&identifier)?.chain_id()?;
+            let blockchain = SupportedBlockchain::from_chain_id(chain_id);
+            Some(CollectionVariant {
+                identifier,
+                chain_id: Some(chain_id),
+                blockchain_name: blockchain.map(|b| b.as_str().to_string()),
+            })
+        })
+        .collect()
+}
+
+/// The default backend: a read-only pool against the on-disk `global.db`
+/// SQLite file shipped by the Python backend. Every query here is checked
+/// against the real schema at build time via `sqlx::query!`, with the
+/// offline cache for `SQLX_OFFLINE=true` builds checked in under `.sqlx/`.
+pub(crate) struct SqliteAssetDataBackend {
+    pool: SqlitePool,
+}
+
+impl SqliteAssetDataBackend {
+    pub(crate) async fn connect(path: &std::path::Path) -> DBOutput<Self> {
+        let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", path.display()))
+            .map_err(sqlx::Error::from)?
+            .read_only(true)
+            .journal_mode(SqliteJournalMode::Wal);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(POOL_SIZE)
+            .connect_with(options)
+            .await?;
+        Ok(Self { pool })
+    }
+
+    pub(crate) fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+}
+
+#[async_trait]
+impl AssetDataBackend for SqliteAssetDataBackend {
+    async fn get_coingecko_id(&self, asset_id: &str) -> DBOutput<Option<String>> {
+        let row = sqlx::query!(
+            "SELECT coingecko FROM common_asset_details WHERE identifier = ?",
+            asset_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.and_then(|r| r.coingecko))
+    }
+
+    async fn get_collection_main_asset(&self, asset_id: &str) -> DBOutput<Option<String>> {
+        let main_asset = sqlx::query!(
+            r#"SELECT ac.main_asset as "main_asset!" FROM asset_collections AS ac
+               INNER JOIN multiasset_mappings AS mm ON mm.collection_id = ac.id
+               WHERE mm.asset = ?"#,
+            asset_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .map(|r| r.main_asset);
+
+        let Some(main_asset) = main_asset else {
+            return Ok(None);
+        };
+
+        let is_wrapped_native_case = parse_asset_identifier(&main_asset).is_none()
+            && parse_asset_identifier(asset_id).is_some();
+        if is_wrapped_native_case {
+            let variants = self.get_collection_variants(asset_id).await?;
+            if let Some(mainnet_variant) = variants
+                .iter()
+                .find(|variant| variant.chain_id == Some(SupportedBlockchain::Ethereum.chain_id()))
+            {
+                return Ok(Some(mainnet_variant.identifier.clone()));
+            }
+        }
+
+        Ok(Some(main_asset))
+    }
+
+    async fn get_collection_variants(&self, asset_id: &str) -> DBOutput<Vec<CollectionVariant>> {
+        let members: Vec<String> = sqlx::query!(
+            r#"SELECT mm2.asset as "asset!" FROM multiasset_mappings AS mm1
+               INNER JOIN multiasset_mappings AS mm2 ON mm2.collection_id = mm1.collection_id
+               WHERE mm1.asset = ?"#,
+            asset_id
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|r| r.asset)
+        .collect();
+
+        Ok(collection_variants_from_members(members))
+    }
+
+    async fn get_rpc_nodes(&self, blockchain: SupportedBlockchain) -> DBOutput<Vec<RpcNode>> {
+        let blockchain_name = blockchain.as_str();
+        let rows = sqlx::query!(
+            r#"SELECT name as "name!", endpoint as "endpoint!", weight as "weight!", owned as "owned!"
+               FROM default_rpc_nodes
+               WHERE blockchain = ? AND name NOT LIKE '%etherscan%' AND active = 1
+               AND (CAST(weight as decimal) != 0 OR owned == 1)
+               ORDER BY name"#,
+            blockchain_name
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| RpcNode {
+                name: row.name,
+                endpoint: row.endpoint,
+                blockchain: blockchain.clone(),
+                weight: row.weight.parse().unwrap_or(0.0),
+                owned: row.owned != 0,
+            })
+            .collect())
+    }
+
+    async fn get_assets_in_collection(&self, collection_id: u32) -> DBOutput<Vec<String>> {
+        let collection_id = collection_id as i64;
+        let rows = sqlx::query!(
+            r#"SELECT asset as "asset!" FROM multiasset_mappings WHERE collection_id = ?"#,
+            collection_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| row.asset).collect())
+    }
+
+    async fn is_uniswap_position(&self, asset_id: &str) -> DBOutput<bool> {
+        let row = sqlx::query!(
+            "SELECT 1 as present FROM evm_tokens
+             WHERE identifier = ?
+             AND protocol IN ('UNI-V3', 'UNI-V4')
+             LIMIT 1",
+            asset_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.is_some())
+    }
+
+    async fn schema_version(&self) -> DBOutput<u32> {
+        let row = sqlx::query!(r#"SELECT value as "value!" FROM settings WHERE name = 'version'"#)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.value.parse().unwrap_or(0))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// A Postgres-backed alternative for deployments where the global asset
+/// data lives in a shared instance rather than a local file (multi-user
+/// servers, hosted setups). Unlike `SqliteAssetDataBackend`, these queries
+/// go through plain `sqlx::query`/`query_as` rather than the `query!`
+/// macros: the macros check a query against a real schema at build time,
+/// and colibri doesn't ship (or have access to, in this environment) a
+/// Postgres copy of the global asset schema the way it ships `global.db`
+/// for SQLite, so there's nothing for them to check against yet. These
+/// queries are still validated by `rotki`'s shared schema when it exists.
+pub(crate) struct PostgresAssetDataBackend {
+    pool: PgPool,
+}
+
+impl PostgresAssetDataBackend {
+    pub(crate) async fn connect(url: &str) -> DBOutput<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(POOL_SIZE)
+            .connect(url)
+            .await?;
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl AssetDataBackend for PostgresAssetDataBackend {
+    async fn get_coingecko_id(&self, asset_id: &str) -> DBOutput<Option<String>> {
+        let row = sqlx::query("SELECT coingecko FROM common_asset_details WHERE identifier = $1")
+            .bind(asset_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.and_then(|row| row.try_get::<Option<String>, _>("coingecko").ok().flatten()))
+    }
+
+    async fn get_collection_main_asset(&self, asset_id: &str) -> DBOutput<Option<String>> {
+        let row = sqlx::query(
+            "SELECT ac.main_asset FROM asset_collections AS ac
+             INNER JOIN multiasset_mappings AS mm ON mm.collection_id = ac.id
+             WHERE mm.asset = $1",
+        )
+        .bind(asset_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(main_asset) = row.and_then(|row| row.try_get::<String, _>("main_asset").ok()) else {
+            return Ok(None);
+        };
+
+        let is_wrapped_native_case = parse_asset_identifier(&main_asset).is_none()
+            && parse_asset_identifier(asset_id).is_some();
+        if is_wrapped_native_case {
+            let variants = self.get_collection_variants(asset_id).await?;
+            if let Some(mainnet_variant) = variants
+                .iter()
+                .find(|variant| variant.chain_id == Some(SupportedBlockchain::Ethereum.chain_id()))
+            {
+                return Ok(Some(mainnet_variant.identifier.clone()));
+            }
+        }
+
+        Ok(Some(main_asset))
+    }
+
+    async fn get_collection_variants(&self, asset_id: &str) -> DBOutput<Vec<CollectionVariant>> {
+        let rows = sqlx::query(
+            "SELECT mm2.asset FROM multiasset_mappings AS mm1
+             INNER JOIN multiasset_mappings AS mm2 ON mm2.collection_id = mm1.collection_id
+             WHERE mm1.asset = $1",
+        )
+        .bind(asset_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let members = rows
+            .into_iter()
+            .filter_map(|row| row.try_get::<String, _>("asset").ok())
+            .collect();
+        Ok(collection_variants_from_members(members))
+    }
+
+    async fn get_rpc_nodes(&self, blockchain: SupportedBlockchain) -> DBOutput<Vec<RpcNode>> {
+        let rows = sqlx::query(
+            "SELECT name, endpoint, weight, owned FROM default_rpc_nodes
+             WHERE blockchain = $1 AND name NOT LIKE '%etherscan%' AND active = true
+             AND (CAST(weight as decimal) != 0 OR owned = true)
+             ORDER BY name",
+        )
+        .bind(blockchain.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let name: String = row.try_get("name").ok()?;
+                let endpoint: String = row.try_get("endpoint").ok()?;
+                let weight_raw: String = row.try_get("weight").ok()?;
+                let owned: bool = row.try_get("owned").ok()?;
+                Some(RpcNode {
+                    name,
+                    endpoint,
+                    blockchain: blockchain.clone(),
+                    weight: weight_raw.parse().unwrap_or(0.0),
+                    owned,
+                })
+            })
+            .collect())
+    }
+
+    async fn get_assets_in_collection(&self, collection_id: u32) -> DBOutput<Vec<String>> {
+        let rows = sqlx::query("SELECT asset FROM multiasset_mappings WHERE collection_id = $1")
+            .bind(collection_id as i64)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| row.try_get::<String, _>("asset").ok())
+            .collect())
+    }
+
+    async fn is_uniswap_position(&self, asset_id: &str) -> DBOutput<bool> {
+        let row = sqlx::query(
+            "SELECT 1 FROM evm_tokens
+             WHERE identifier = $1
+             AND protocol IN ('UNI-V3', 'UNI-V4')
+             LIMIT 1",
+        )
+        .bind(asset_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.is_some())
+    }
+
+    async fn schema_version(&self) -> DBOutput<u32> {
+        let row = sqlx::query("SELECT value FROM settings WHERE name = 'version'")
+            .fetch_one(&self.pool)
+            .await?;
+        let value: String = row.try_get("value")?;
+        Ok(value.parse().unwrap_or(0))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
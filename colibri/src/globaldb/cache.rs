@@ -0,0 +1,60 @@
+use std::num::NonZeroUsize;
+
+use lru::LruCache;
+use tokio::sync::Mutex;
+
+/// Bounded, process-local cache sitting in front of the handful of
+/// `GlobalDB` lookups whose results are immutable for the lifetime of a
+/// `global.db` -- a CoinGecko id or a collection's main asset for a given
+/// `asset_id` never changes without the Python backend rewriting the
+/// file, so re-querying `AssetDataBackend` for the same identifier on
+/// every balance/price resolution only adds pool contention for no
+/// benefit. Keyed by `asset_id`, one entry per cached lookup kind so a
+/// hot collection lookup doesn't evict hot CoinGecko ids or vice versa.
+pub(crate) struct LookupCache {
+    coingecko_id: Mutex<LruCache<String, Option<String>>>,
+    collection_main_asset: Mutex<LruCache<String, Option<String>>>,
+}
+
+impl LookupCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            coingecko_id: Mutex::new(LruCache::new(capacity)),
+            collection_main_asset: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    pub(crate) async fn get_coingecko_id(&self, asset_id: &str) -> Option<Option<String>> {
+        self.coingecko_id.lock().await.get(asset_id).cloned()
+    }
+
+    pub(crate) async fn put_coingecko_id(&self, asset_id: &str, value: Option<String>) {
+        self.coingecko_id
+            .lock()
+            .await
+            .put(asset_id.to_string(), value);
+    }
+
+    pub(crate) async fn get_collection_main_asset(&self, asset_id: &str) -> Option<Option<String>> {
+        self.collection_main_asset
+            .lock()
+            .await
+            .get(asset_id)
+            .cloned()
+    }
+
+    pub(crate) async fn put_collection_main_asset(&self, asset_id: &str, value: Option<String>) {
+        self.collection_main_asset
+            .lock()
+            .await
+            .put(asset_id.to_string(), value);
+    }
+
+    /// Drops every cached entry, for callers that learn the Python backend
+    /// just finished a global-db update (see `GlobalDB::clear_cache`).
+    pub(crate) async fn clear(&self) {
+        self.coingecko_id.lock().await.clear();
+        self.collection_main_asset.lock().await.clear();
+    }
+}
@@ -1,9 +1,22 @@
 use thiserror::Error;
 
+use crate::globaldb::verification::VerificationError;
+
 #[derive(Debug, Error)]
 pub enum DBError {
     #[error("DB QUERY ERROR DUE TO {0}")]
     Sql(#[from] rusqlite::Error),
+    #[error("failed to verify global.db package: {0}")]
+    Verification(#[from] VerificationError),
+    #[error("globaldb sqlx error: {0}")]
+    Sqlx(#[from] sqlx::Error),
+    #[error("unsupported globaldb backend: {0}")]
+    UnsupportedBackend(&'static str),
+    #[error(
+        "global.db schema version {found} predates the {required} colibri requires -- \
+         the rotki python backend hasn't finished upgrading it yet"
+    )]
+    SchemaTooOld { found: u32, required: u32 },
     // other variants...
 }
 
@@ -1,115 +1,219 @@
 use crate::blockchain::{RpcNode, SupportedBlockchain};
-use rusqlite::{Connection, Result};
+use crate::globaldb::backend::{AssetDataBackend, PostgresAssetDataBackend, SqliteAssetDataBackend};
+use crate::globaldb::cache::LookupCache;
+use crate::globaldb::errors::{DBError, DBOutput};
+use crate::globaldb::verification::{self, TrustedKeyring, VerificationStatus};
+use rusqlite::Connection;
+use serde::Serialize;
+use sqlx::SqlitePool;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// One chain-specific deployment of a token that belongs to the same
+/// `asset_collections` group as the asset `get_collection_variants` was
+/// queried with, e.g. ethereum's and optimism's USDC for a USDC query.
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionVariant {
+    pub identifier: String,
+    pub chain_id: Option<u64>,
+    /// `SupportedBlockchain::as_str()`, when the chain is one of the
+    /// well-known ones; `None` for EVM chains colibri doesn't recognize.
+    pub blockchain_name: Option<String>,
+}
+
+/// Oldest `global.db` schema version colibri can run against. Bumped
+/// whenever a lookup in this module starts relying on a table or column
+/// introduced by a rotki python-backend global-db upgrade; `GlobalDB::new`
+/// refuses to open an older DB rather than let the first query that
+/// touches the missing schema fail with an opaque SQL error.
+pub const MIN_SUPPORTED_GLOBALDB_VERSION: u32 = 9;
+
+/// Default entry count for the `coingecko_id`/`collection_main_asset`
+/// lookup cache (see `cache::LookupCache`), used by callers that don't
+/// have a reason to tune it -- generous enough to hold every asset colibri
+/// is likely to resolve in a session without tracking memory use per entry.
+pub const DEFAULT_LOOKUP_CACHE_CAPACITY: usize = 1024;
+
 #[derive(Clone)]
 pub struct GlobalDB {
+    /// Backend serving every lookup against the tables the Python backend
+    /// owns (assets, RPC nodes, collections, ...). Chosen by `new` /
+    /// `new_with_backend_url` from the asset data source's URL scheme --
+    /// `sqlite://` (the default, a local `global.db` file) or
+    /// `postgres://` -- so the rest of colibri stays backend-agnostic.
+    /// See `globaldb::backend`.
+    backend: Arc<dyn AssetDataBackend>,
+    /// Single read-write connection for colibri's own icon-media tables
+    /// (see `media.rs`). These are colibri's own cache, created locally by
+    /// `init_media_schema` regardless of where the Python-owned asset data
+    /// lives, so this always stays a local SQLite file even when `backend`
+    /// is `PostgresAssetDataBackend`.
     pub conn: Arc<Mutex<Connection>>,
+    /// Whether the DB file verified against the trusted keyring when this
+    /// `GlobalDB` was opened. See `globaldb::verification`.
+    pub verification_status: VerificationStatus,
+    /// Bounded cache in front of `get_coingecko_id`/`get_collection_main_asset`,
+    /// whose results never change without the Python backend rewriting
+    /// `global.db` -- see `cache::LookupCache` and `clear_cache`.
+    cache: Arc<LookupCache>,
 }
 
-/// The GlobalDB handler for Colibri
-/// We assume its updated and up to date
-/// from the rotki python backend
+/// The GlobalDB handler for Colibri. The rotki python backend owns the
+/// schema and is expected to keep it up to date; `new` checks
+/// `schema_version` against `MIN_SUPPORTED_GLOBALDB_VERSION` rather than
+/// just assuming so, since an in-progress upgrade on the python side would
+/// otherwise surface as a confusing SQL failure deep in some unrelated
+/// request.
 impl GlobalDB {
-    pub async fn new(path: PathBuf) -> Result<Self> {
-        let conn = Connection::open(path)?;
+    /// Opens `path` as colibri's local media cache (see `conn` above) and
+    /// as the default SQLite asset-data backend. Equivalent to
+    /// `new_with_backend_url(path.clone(), "sqlite://<path>", cache_capacity)`.
+    pub async fn new(path: PathBuf, cache_capacity: usize) -> DBOutput<Self> {
+        let url = format!("sqlite://{}", path.display());
+        Self::new_with_backend_url(path, &url, cache_capacity).await
+    }
+
+    /// Opens `path` as colibri's local media cache, same as `new`, but
+    /// serves asset-data lookups from whatever `asset_data_url` points at
+    /// -- `sqlite://...` for another local file, or `postgres://...` for a
+    /// shared instance. `cache_capacity` bounds the `get_coingecko_id`/
+    /// `get_collection_main_asset` lookup cache (see `cache::LookupCache`).
+    pub async fn new_with_backend_url(
+        path: PathBuf,
+        asset_data_url: &str,
+        cache_capacity: usize,
+    ) -> DBOutput<Self> {
+        let verification_status = verification::verify_db_file(&path, &TrustedKeyring::trusted())?;
+
+        let conn = Connection::open(path.clone())?;
+        // WAL mode is a property of the database file, not the connection,
+        // but set it explicitly here since this is the first connection
+        // opened and the Python backend may not have enabled it.
+        conn.pragma_update(None, "journal_mode", "WAL")?;
         let conn = Arc::new(Mutex::new(conn));
 
-        Ok(GlobalDB { conn })
+        let backend: Arc<dyn AssetDataBackend> = if let Some(url) = asset_data_url
+            .strip_prefix("postgres://")
+            .or_else(|| asset_data_url.strip_prefix("postgresql://"))
+        {
+            Arc::new(PostgresAssetDataBackend::connect(&format!("postgres://{url}")).await?)
+        } else {
+            let sqlite_path = asset_data_url
+                .strip_prefix("sqlite://")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from(asset_data_url));
+            Arc::new(SqliteAssetDataBackend::connect(&sqlite_path).await?)
+        };
+
+        let found_version = backend.schema_version().await?;
+        if found_version < MIN_SUPPORTED_GLOBALDB_VERSION {
+            return Err(DBError::SchemaTooOld {
+                found: found_version,
+                required: MIN_SUPPORTED_GLOBALDB_VERSION,
+            });
+        }
+
+        let globaldb = GlobalDB {
+            conn,
+            backend,
+            verification_status,
+            cache: Arc::new(LookupCache::new(cache_capacity)),
+        };
+        globaldb.init_media_schema().await?;
+        Ok(globaldb)
+    }
+
+    /// Whether the loaded asset data is cryptographically authenticated,
+    /// i.e. it carried a detached signature that verified against the
+    /// trusted keyring.
+    pub fn is_authenticated(&self) -> bool {
+        self.verification_status == VerificationStatus::Verified
     }
 
-    pub async fn get_coingecko_id(&self, asset_id: &str) -> Result<Option<String>> {
-        let conn = self.conn.lock().await;
-        let mut stmt =
-            conn.prepare("SELECT coingecko FROM common_asset_details WHERE identifier = ?")?;
-        let mut rows = stmt.query([asset_id])?;
+    /// Exposes the SQLite pool backing `backend` to other modules in
+    /// `globaldb` (currently just `assets.rs`'s variable-arity "all
+    /// assets" join, which `AssetDataBackend` doesn't cover yet). Errors
+    /// out for a Postgres-backed `GlobalDB` rather than panicking, since
+    /// that combination is a real, if currently unsupported, deployment.
+    pub(crate) fn sqlite_pool(&self) -> DBOutput<&sqlx::SqlitePool> {
+        self.backend
+            .as_any()
+            .downcast_ref::<SqliteAssetDataBackend>()
+            .map(SqliteAssetDataBackend::pool)
+            .ok_or(DBError::UnsupportedBackend(
+                "get_assets_mappings requires a sqlite-backed GlobalDB",
+            ))
+    }
 
-        if let Some(row) = rows.next()? {
-            Ok(row.get(0)?)
-        } else {
-            Ok(None)
+    pub async fn get_coingecko_id(&self, asset_id: &str) -> DBOutput<Option<String>> {
+        if let Some(cached) = self.cache.get_coingecko_id(asset_id).await {
+            return Ok(cached);
         }
+        let value = self.backend.get_coingecko_id(asset_id).await?;
+        self.cache.put_coingecko_id(asset_id, value.clone()).await;
+        Ok(value)
     }
 
-    pub async fn get_collection_main_asset(&self, asset_id: &str) -> Result<Option<String>> {
-        const WETH_IDENTIFIERS: [&str; 7] = [
-            // Handle WETH differently since it's in the ETH collection and we want WETH icon
-            "eip155:1/erc20:0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
-            "eip155:10/erc20:0x4200000000000000000000000000000000000006",
-            "eip155:100/erc20:0x6A023CCd1ff6F2045C3309768eAd9E68F978f6e1",
-            "eip155:8453/erc20:0x4200000000000000000000000000000000000006",
-            "eip155:534352/erc20:0x5300000000000000000000000000000000000004",
-            "eip155:137/erc20:0x7ceB23fD6bC0adD59E62ac25578270cFf1b9f619",
-            "eip155:42161/erc20:0x82aF49447D8a07e3bd95BD0d56f35241523fBab1",
-        ];
-        if WETH_IDENTIFIERS.contains(&asset_id) {
-            return Ok(Some(WETH_IDENTIFIERS[0].to_string()));
+    /// Returns the collection's recorded `main_asset` for `asset_id`, with
+    /// one adjustment: the Python backend groups a chain's wrapped-native
+    /// token (WETH, WMATIC, ...) into the *native coin's* collection, so its
+    /// `main_asset` is the plain native identifier (e.g. `"ETH"`) rather
+    /// than a token. A caller asking about one of those wrapped variants
+    /// wants its own icon, not the native coin's, so in that case this
+    /// resolves to the Ethereum-mainnet wrapped variant instead, derived
+    /// from collection membership via `get_collection_variants` rather than
+    /// a hardcoded address list.
+    pub async fn get_collection_main_asset(&self, asset_id: &str) -> DBOutput<Option<String>> {
+        if let Some(cached) = self.cache.get_collection_main_asset(asset_id).await {
+            return Ok(cached);
         }
-        let conn = self.conn.lock().await;
-        conn.prepare(
-            "SELECT ac.main_asset FROM asset_collections AS ac
-             INNER JOIN multiasset_mappings AS mm ON mm.collection_id = ac.id
-             WHERE mm.asset = ?",
-        )
-        .and_then(|mut stmt| {
-            // Execute the query with the identifier parameter
-            stmt.query_row([asset_id], |row| row.get(0))
-                .map(Some)
-                .or_else(|e| match e {
-                    rusqlite::Error::QueryReturnedNoRows => Ok(None),
-                    _ => Err(e),
-                })
-        })
+        let value = self.backend.get_collection_main_asset(asset_id).await?;
+        self.cache
+            .put_collection_main_asset(asset_id, value.clone())
+            .await;
+        Ok(value)
+    }
+
+    /// Returns every other member of `asset_id`'s `asset_collections` group
+    /// (including `asset_id` itself), each tagged with the EVM chain it was
+    /// parsed from -- i.e. the full set of "mirrored" chain-specific
+    /// deployments of the same logical token, so callers can map a token on
+    /// one chain to its counterpart on another.
+    pub async fn get_collection_variants(&self, asset_id: &str) -> DBOutput<Vec<CollectionVariant>> {
+        self.backend.get_collection_variants(asset_id).await
     }
 
     /// Get all active RPC endpoints for a specific blockchain.
-    pub async fn get_rpc_nodes(&self, blockchain: SupportedBlockchain) -> Result<Vec<RpcNode>> {
-        let conn = self.conn.lock().await;
-        conn.prepare("SELECT name, endpoint FROM default_rpc_nodes WHERE blockchain=? AND name NOT LIKE '%etherscan%' AND active=1 AND (CAST(weight as decimal) != 0 OR owned == 1) ORDER BY name;")
-            .and_then(|mut stmt| {
-                let mut rows = stmt.query(rusqlite::params![blockchain.as_str()])?;
-                let mut nodes = Vec::new();
-                while let Some(row) = rows.next()? {
-                    let name: String = row.get(0)?;
-                    let endpoint: String = row.get(1)?;
-                    nodes.push(RpcNode {
-                        name,
-                        endpoint,
-                        blockchain,
-                    });
-                }
-                Ok(nodes)
-            })
+    pub async fn get_rpc_nodes(&self, blockchain: SupportedBlockchain) -> DBOutput<Vec<RpcNode>> {
+        self.backend.get_rpc_nodes(blockchain).await
     }
 
-    pub async fn get_assets_in_collection(&self, collection_id: u32) -> Result<Vec<String>> {
-        let conn = self.conn.lock().await;
-        conn.prepare("SELECT asset FROM multiasset_mappings WHERE collection_id=?")
-            .and_then(|mut stmt| {
-                let mut rows = stmt.query(rusqlite::params![collection_id])?;
-                let mut assets_in_collection = Vec::new();
-                while let Some(row) = rows.next()? {
-                    let asset_id: String = row.get(0)?;
-                    assets_in_collection.push(asset_id)
-                }
-
-                Ok(assets_in_collection)
-            })
+    pub async fn get_assets_in_collection(&self, collection_id: u32) -> DBOutput<Vec<String>> {
+        self.backend.get_assets_in_collection(collection_id).await
     }
 
     /// Checks if the given asset is a Uniswap V3 or V4 position NFT
-    pub async fn is_uniswap_position(&self, asset_id: &str) -> Result<bool> {
-        let conn = self.conn.lock().await;
-        let mut stmt = conn.prepare(
-            "SELECT 1 FROM evm_tokens
-             WHERE identifier = ?
-             AND protocol IN ('UNI-V3', 'UNI-V4')
-             LIMIT 1",
-        )?;
-        let result = stmt.exists(rusqlite::params![asset_id])?;
-        Ok(result)
+    pub async fn is_uniswap_position(&self, asset_id: &str) -> DBOutput<bool> {
+        self.backend.is_uniswap_position(asset_id).await
+    }
+
+    /// The `global.db` schema version this `GlobalDB` opened against, as
+    /// last recorded by the rotki python backend. Already checked against
+    /// `MIN_SUPPORTED_GLOBALDB_VERSION` in `new`; exposed separately so
+    /// callers that want to report it (e.g. a diagnostics endpoint) don't
+    /// need to re-derive it.
+    pub async fn schema_version(&self) -> DBOutput<u32> {
+        self.backend.schema_version().await
+    }
+
+    /// Drops every entry from the `get_coingecko_id`/
+    /// `get_collection_main_asset` lookup cache. Callers should invoke this
+    /// once they learn the rotki python backend has finished rewriting
+    /// `global.db` (e.g. after an asset update), since the cache otherwise
+    /// has no way of knowing the on-disk rows it memoized are now stale.
+    pub async fn clear_cache(&self) {
+        self.cache.clear().await;
     }
 }
 
@@ -146,7 +250,10 @@ macro_rules! create_globaldb {
         .await
         .expect("Failed to copy globaldb in create_globaldb macro");
 
-        GlobalDB::new(tmp_dir.join("global.db"))
+        GlobalDB::new(
+            tmp_dir.join("global.db"),
+            crate::globaldb::DEFAULT_LOOKUP_CACHE_CAPACITY,
+        )
     }};
 }
 
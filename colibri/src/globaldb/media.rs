@@ -0,0 +1,213 @@
+use crate::globaldb::errors::DBOutput;
+use crate::globaldb::GlobalDB;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+impl GlobalDB {
+    /// Creates the icon media tables if they don't exist yet. Unlike the
+    /// rest of globaldb, these aren't populated by the Python backend --
+    /// they're colibri's own content-addressed icon cache, keyed by the
+    /// digest of the icon bytes so identical icons served under different
+    /// asset identifiers are only ever stored once.
+    pub(crate) async fn init_media_schema(&self) -> rusqlite::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS colibri_icon_media (
+                digest TEXT NOT NULL PRIMARY KEY,
+                mime TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS colibri_asset_icons (
+                asset_id TEXT NOT NULL PRIMARY KEY,
+                digest TEXT NOT NULL REFERENCES colibri_icon_media(digest)
+            );
+            CREATE TABLE IF NOT EXISTS colibri_nft_icon_cache (
+                chain_id INTEGER NOT NULL,
+                contract_address TEXT NOT NULL,
+                token_id TEXT NOT NULL,
+                icon_bytes BLOB NOT NULL,
+                extension TEXT NOT NULL,
+                cached_at INTEGER NOT NULL,
+                PRIMARY KEY (chain_id, contract_address, token_id)
+            );
+            CREATE TABLE IF NOT EXISTS colibri_coingecko_image_cache (
+                asset_id TEXT NOT NULL,
+                size TEXT NOT NULL,
+                image_bytes BLOB NOT NULL,
+                etag TEXT,
+                fetched_at INTEGER NOT NULL,
+                PRIMARY KEY (asset_id, size)
+            );",
+        )
+    }
+
+    /// Returns the `(digest, mime)` registered for `asset_id`, if any.
+    pub async fn get_asset_icon_digest(
+        &self,
+        asset_id: &str,
+    ) -> DBOutput<Option<(String, String)>> {
+        let conn = self.conn.lock().await;
+        conn.prepare(
+            "SELECT m.digest, m.mime FROM colibri_asset_icons AS a
+             INNER JOIN colibri_icon_media AS m ON m.digest = a.digest
+             WHERE a.asset_id = ?",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_row([asset_id], |row| Ok((row.get(0)?, row.get(1)?)))
+                .map(Some)
+                .or_else(|e| match e {
+                    rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                    _ => Err(e),
+                })
+        })
+        .map_err(Into::into)
+    }
+
+    /// Registers `asset_id` as using the icon identified by `digest`,
+    /// recording its `mime` type explicitly so it doesn't need to be
+    /// re-derived from a file extension on every request. Returns `true`
+    /// if `digest` wasn't already known, i.e. the caller still needs to
+    /// write the icon bytes to the content-addressed media store.
+    pub async fn store_asset_icon(
+        &self,
+        asset_id: &str,
+        digest: &str,
+        mime: &str,
+    ) -> DBOutput<bool> {
+        let conn = self.conn.lock().await;
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO colibri_icon_media (digest, mime) VALUES (?, ?)",
+            rusqlite::params![digest, mime],
+        )?;
+        conn.execute(
+            "INSERT OR REPLACE INTO colibri_asset_icons (asset_id, digest) VALUES (?, ?)",
+            rusqlite::params![asset_id, digest],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// Returns the `(icon_bytes, extension, cached_at)` cached for an NFT
+    /// position, if any. `cached_at` is a unix timestamp in seconds, left for
+    /// the caller to compare against its own TTL since what counts as stale
+    /// varies by use case.
+    pub async fn get_nft_icon_cache(
+        &self,
+        chain_id: u64,
+        contract_address: &str,
+        token_id: &str,
+    ) -> DBOutput<Option<(Vec<u8>, String, i64)>> {
+        let conn = self.conn.lock().await;
+        conn.prepare(
+            "SELECT icon_bytes, extension, cached_at FROM colibri_nft_icon_cache
+             WHERE chain_id = ? AND contract_address = ? AND token_id = ?",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_row(
+                rusqlite::params![chain_id as i64, contract_address, token_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                _ => Err(e),
+            })
+        })
+        .map_err(Into::into)
+    }
+
+    /// Caches the decoded icon for an NFT position, keyed by
+    /// `(chain_id, contract_address, token_id)`, stamped with the current
+    /// time so callers can apply their own TTL.
+    pub async fn store_nft_icon_cache(
+        &self,
+        chain_id: u64,
+        contract_address: &str,
+        token_id: &str,
+        icon_bytes: &[u8],
+        extension: &str,
+    ) -> DBOutput<()> {
+        let cached_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT OR REPLACE INTO colibri_nft_icon_cache
+             (chain_id, contract_address, token_id, icon_bytes, extension, cached_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                chain_id as i64,
+                contract_address,
+                token_id,
+                icon_bytes,
+                extension,
+                cached_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Explicit invalidation hook for callers that know a position's
+    /// on-chain state (ticks/liquidity) changed since it was cached.
+    pub async fn invalidate_nft_icon_cache(
+        &self,
+        chain_id: u64,
+        contract_address: &str,
+        token_id: &str,
+    ) -> DBOutput<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "DELETE FROM colibri_nft_icon_cache
+             WHERE chain_id = ? AND contract_address = ? AND token_id = ?",
+            rusqlite::params![chain_id as i64, contract_address, token_id],
+        )?;
+        Ok(())
+    }
+
+    /// Returns the `(image_bytes, etag, fetched_at)` cached for a CoinGecko
+    /// asset image at a given size, if any. `fetched_at` is a unix timestamp
+    /// in seconds, left for the caller to compare against its own TTL.
+    pub async fn get_coingecko_image_cache(
+        &self,
+        asset_id: &str,
+        size: &str,
+    ) -> DBOutput<Option<(Vec<u8>, Option<String>, i64)>> {
+        let conn = self.conn.lock().await;
+        conn.prepare(
+            "SELECT image_bytes, etag, fetched_at FROM colibri_coingecko_image_cache
+             WHERE asset_id = ? AND size = ?",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_row(rusqlite::params![asset_id, size], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                _ => Err(e),
+            })
+        })
+        .map_err(Into::into)
+    }
+
+    /// Caches a CoinGecko asset image at a given size, stamped with the
+    /// current time so callers can apply their own TTL and revalidate with
+    /// `etag` (if CoinGecko sent one) once it goes stale.
+    pub async fn store_coingecko_image_cache(
+        &self,
+        asset_id: &str,
+        size: &str,
+        image_bytes: &[u8],
+        etag: Option<&str>,
+    ) -> DBOutput<()> {
+        let fetched_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT OR REPLACE INTO colibri_coingecko_image_cache
+             (asset_id, size, image_bytes, etag, fetched_at) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params![asset_id, size, image_bytes, etag, fetched_at],
+        )?;
+        Ok(())
+    }
+}
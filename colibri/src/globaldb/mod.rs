@@ -1,6 +1,11 @@
 mod assets;
+mod backend;
+mod cache;
 mod errors;
 mod handler;
+mod media;
+mod verification;
 
 pub use assets::{AssetMappings, CollectionInfo};
-pub use handler::GlobalDB;
+pub use handler::{CollectionVariant, GlobalDB, DEFAULT_LOOKUP_CACHE_CAPACITY};
+pub use verification::VerificationStatus;
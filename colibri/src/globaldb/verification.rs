@@ -0,0 +1,152 @@
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Algorithm header byte of a detached signature file, so a future key
+/// rotation to a different scheme doesn't need a new file extension or a
+/// breaking format change -- old signature files just keep carrying their
+/// own header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SignatureAlgorithm {
+    Ed25519,
+    /// Reserved for a future rotation; not implemented yet.
+    EcdsaP256,
+}
+
+impl SignatureAlgorithm {
+    fn from_header(byte: u8) -> Option<Self> {
+        match byte {
+            0x01 => Some(Self::Ed25519),
+            0x02 => Some(Self::EcdsaP256),
+            _ => None,
+        }
+    }
+}
+
+/// The outcome of verifying a `global.db` package against the trusted
+/// keyring, exposed through `GlobalDB` so the API can report whether the
+/// loaded asset data is authenticated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationStatus {
+    /// No `.sig` file was found alongside the DB, e.g. a developer's local
+    /// `global.db` that was never meant to be signed, or the keyring has no
+    /// key to check a present `.sig` against. Not an error on its
+    /// own -- only a *present but invalid* signature is treated as
+    /// tampering.
+    Unsigned,
+    /// The detached signature matched one of the trusted keys.
+    Verified,
+}
+
+#[derive(Debug, Error)]
+pub enum VerificationError {
+    #[error("failed to read {0}: {1}")]
+    Io(String, std::io::Error),
+    #[error("signature file {0} is malformed: {1}")]
+    Malformed(String, String),
+    #[error("signature algorithm {0:#04x} is not supported by this build")]
+    UnsupportedAlgorithm(u8),
+    #[error("{0} did not verify against any trusted key")]
+    InvalidSignature(String),
+}
+
+/// Trusted Ed25519 public keys (raw 32-byte form), newest first. Empty until
+/// rotki's real asset-db signing key is published here -- a placeholder key
+/// would make verification vacuous (every signature would need to be forged
+/// against a key nobody controls, which is no verification at all) while
+/// still hard-failing `GlobalDB::new` the instant a real signed `global.db`
+/// ships, since it wouldn't verify against the placeholder either. Until
+/// this is populated, `verify_db_file` treats a signed DB the same as an
+/// unsigned one rather than either of those. A key rotation adds the new key
+/// here ahead of time so both it and the outgoing key verify during the
+/// overlap period; an old key is only removed once no supported release
+/// still ships files signed with it.
+const TRUSTED_ED25519_KEYS: &[[u8; 32]] = &[];
+
+/// The set of public keys this build trusts to sign a `global.db` package.
+pub struct TrustedKeyring {
+    ed25519_keys: Vec<VerifyingKey>,
+}
+
+impl TrustedKeyring {
+    pub fn new(ed25519_keys: Vec<VerifyingKey>) -> Self {
+        Self { ed25519_keys }
+    }
+
+    /// The keyring shipped with this binary, decoded from `TRUSTED_ED25519_KEYS`.
+    pub fn trusted() -> Self {
+        Self::new(
+            TRUSTED_ED25519_KEYS
+                .iter()
+                .map(|bytes| {
+                    VerifyingKey::from_bytes(bytes).expect("trusted public key is malformed")
+                })
+                .collect(),
+        )
+    }
+
+    fn verify_ed25519(&self, digest: &[u8; 32], signature: &Signature) -> bool {
+        self.ed25519_keys
+            .iter()
+            .any(|key| key.verify(digest, signature).is_ok())
+    }
+}
+
+fn signature_path_for(db_path: &Path) -> PathBuf {
+    let mut file_name = db_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".sig");
+    db_path.with_file_name(file_name)
+}
+
+/// Verifies `db_path` against its detached signature file (`db_path` with
+/// `.sig` appended), computing SHA-256 over the DB bytes and checking it
+/// against every key in `keyring`. Returns `Ok(Unsigned)` if no signature
+/// file exists at all, or if `keyring` holds no keys.
+pub fn verify_db_file(
+    db_path: &Path,
+    keyring: &TrustedKeyring,
+) -> Result<VerificationStatus, VerificationError> {
+    let sig_path = signature_path_for(db_path);
+
+    let sig_bytes = match std::fs::read(&sig_path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(VerificationStatus::Unsigned)
+        }
+        Err(e) => return Err(VerificationError::Io(sig_path.display().to_string(), e)),
+    };
+
+    if keyring.ed25519_keys.is_empty() {
+        // No trusted key is configured for this build yet (see
+        // TRUSTED_ED25519_KEYS) -- there's nothing to check the signature
+        // against, so this DB is treated the same as an unsigned one instead
+        // of either trusting it vacuously or hard-failing `GlobalDB::new`.
+        return Ok(VerificationStatus::Unsigned);
+    }
+
+    let (&header, signature_bytes) = sig_bytes.split_first().ok_or_else(|| {
+        VerificationError::Malformed(sig_path.display().to_string(), "empty signature file".to_string())
+    })?;
+    let algorithm =
+        SignatureAlgorithm::from_header(header).ok_or(VerificationError::UnsupportedAlgorithm(header))?;
+
+    let db_bytes = std::fs::read(db_path)
+        .map_err(|e| VerificationError::Io(db_path.display().to_string(), e))?;
+    let digest: [u8; 32] = Sha256::digest(&db_bytes).into();
+
+    match algorithm {
+        SignatureAlgorithm::Ed25519 => {
+            let signature = Signature::from_slice(signature_bytes).map_err(|e| {
+                VerificationError::Malformed(sig_path.display().to_string(), e.to_string())
+            })?;
+            if keyring.verify_ed25519(&digest, &signature) {
+                Ok(VerificationStatus::Verified)
+            } else {
+                Err(VerificationError::InvalidSignature(db_path.display().to_string()))
+            }
+        }
+        SignatureAlgorithm::EcdsaP256 => Err(VerificationError::UnsupportedAlgorithm(header)),
+    }
+}
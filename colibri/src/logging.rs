@@ -1,11 +1,79 @@
 use crate::args::Args;
 
 use std::fmt;
+use std::io::{self, Write};
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 
 use file_rotate::{compression::Compression, suffix::AppendCount, ContentLimit, FileRotate};
-use std::sync::Mutex;
 use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, Registry};
+
+/// Handle used to reload the active `EnvFilter` at runtime, e.g. from the
+/// `/logging/level` endpoint, without having to restart colibri.
+pub type LogReloadHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Where log lines are written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LogDestination {
+    Stdout,
+    Stderr,
+    File(PathBuf),
+}
+
+impl FromStr for LogDestination {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "-" | "stdout" => LogDestination::Stdout,
+            "stderr" => LogDestination::Stderr,
+            path => LogDestination::File(PathBuf::from(path)),
+        })
+    }
+}
+
+/// A `FileRotate` sink that can be atomically redirected to a different
+/// file at runtime via [`LogFileHandle::change_log_file`], e.g. when the
+/// user starts a new diagnostic session or the data directory is relocated.
+#[derive(Clone)]
+pub struct LogFileHandle(Arc<Mutex<FileRotate>>);
+
+impl LogFileHandle {
+    fn new(file: FileRotate) -> Self {
+        Self(Arc::new(Mutex::new(file)))
+    }
+
+    /// Atomically swaps the active log file for a fresh `FileRotate` at `path`.
+    pub fn change_log_file(
+        &self,
+        path: PathBuf,
+        max_logfiles_num: usize,
+        max_size_in_mb: usize,
+    ) {
+        let new_file = FileRotate::new(
+            path,
+            AppendCount::new(max_logfiles_num),
+            ContentLimit::BytesSurpassed(10usize.pow(6) * max_size_in_mb),
+            Compression::None,
+            #[cfg(unix)]
+            None,
+        );
+        *self.0.lock().unwrap() = new_file;
+    }
+}
+
+impl Write for LogFileHandle {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.lock().unwrap().flush()
+    }
+}
 
 #[repr(usize)]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
@@ -22,6 +90,11 @@ pub enum RotkiLogLevel {
 impl From<RotkiLogLevel> for LevelFilter {
     fn from(val: RotkiLogLevel) -> Self {
         match val {
+            // `tracing`'s `LevelFilter` only has five levels, so `Critical`
+            // is filtered the same as `Error`. The two remain visually
+            // distinct at the output level: events logged via the
+            // `critical!` macro carry a `severity="critical"` field (see
+            // below), so they can still be told apart and alerted on.
             RotkiLogLevel::Critical | RotkiLogLevel::Error => LevelFilter::ERROR,
             RotkiLogLevel::Warning => LevelFilter::WARN,
             RotkiLogLevel::Info => LevelFilter::INFO,
@@ -78,37 +151,170 @@ impl fmt::Display for RotkiLogLevel {
     }
 }
 
-// Configure logging for the app. We allow logging to a system file
-// or to the stdout. If logs are stored in files they are rotated
-// based on size and there is a max of `max_logfiles_num` files saved.
-pub fn config_logging(args: Args) {
-    let filter = EnvFilter::builder()
-        .with_default_directive(Into::<LevelFilter>::into(args.log_level).into())
+/// Output format used to render log lines.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LogFormat {
+    #[default]
+    Compact,
+    Full,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "COMPACT" => Ok(LogFormat::Compact),
+            "FULL" => Ok(LogFormat::Full),
+            "JSON" => Ok(LogFormat::Json),
+            _ => Err(format!("Unknown log format: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFormat::Compact => write!(f, "compact"),
+            LogFormat::Full => write!(f, "full"),
+            LogFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Logs an event at the `error` level tagged with a `severity="critical"`
+/// field, so it renders distinctly from ordinary errors (the field shows up
+/// in the compact/full/JSON output alike) and can be grepped or alerted on
+/// independently, e.g. for DB corruption or a failed balance reconciliation.
+#[macro_export]
+macro_rules! critical {
+    ($($arg:tt)*) => {
+        tracing::error!(severity = "critical", $($arg)*)
+    };
+}
+
+/// Builds the `EnvFilter` used to gate emitted events.
+///
+/// `level` is used as the default directive applied to every target that
+/// isn't otherwise overridden. `filters`, when given, is a comma-separated
+/// list of `target=level` directives following the same syntax as
+/// `RUST_LOG` (e.g. `rotki::blockchain=debug,hyper=off`), letting callers
+/// silence or amplify individual modules without changing the global level.
+pub fn build_env_filter(level: RotkiLogLevel, filters: Option<&str>) -> EnvFilter {
+    let mut filter = EnvFilter::builder()
+        .with_default_directive(Into::<LevelFilter>::into(level).into())
         .parse("")
         .unwrap();
 
-    let log_to_file = FileRotate::new(
-        args.logfile_path.clone(),
-        AppendCount::new(args.max_logfiles_num),
-        ContentLimit::BytesSurpassed(10usize.pow(6) * args.max_size_in_mb),
-        Compression::None,
-        #[cfg(unix)]
-        None,
+    for directive in filters
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        match directive.parse() {
+            Ok(directive) => filter = filter.add_directive(directive),
+            Err(e) => eprintln!("Ignoring invalid log filter directive '{}': {}", directive, e),
+        }
+    }
+
+    filter
+}
+
+/// Handles returned by [`config_logging`] for controlling the logger at
+/// runtime once the process is up.
+pub struct LoggingHandles {
+    /// Reloads the active `EnvFilter`, e.g. from the `/logging/level` endpoint.
+    pub reload: LogReloadHandle,
+    /// Set when logs are sent to a file; lets callers hot-swap the log file,
+    /// e.g. when the data directory changes. `None` when logging to
+    /// stdout/stderr, since there is nothing to swap.
+    pub file: Option<LogFileHandle>,
+}
+
+// Builds the `BoxMakeWriter`/ansi pair for `destination`, plus the
+// `LogFileHandle` to expose when the destination is a file.
+fn build_writer(
+    destination: &LogDestination,
+    max_logfiles_num: usize,
+    max_size_in_mb: usize,
+) -> (BoxMakeWriter, bool, Option<LogFileHandle>) {
+    match destination {
+        LogDestination::Stdout => (BoxMakeWriter::new(std::io::stdout), true, None),
+        LogDestination::Stderr => (BoxMakeWriter::new(std::io::stderr), true, None),
+        LogDestination::File(path) => {
+            let file = FileRotate::new(
+                path.clone(),
+                AppendCount::new(max_logfiles_num),
+                ContentLimit::BytesSurpassed(10usize.pow(6) * max_size_in_mb),
+                Compression::None,
+                #[cfg(unix)]
+                None,
+            );
+            let handle = LogFileHandle::new(file);
+            (BoxMakeWriter::new(handle.clone()), false, Some(handle))
+        }
+    }
+}
+
+// Configure logging for the app. We allow logging to a system file, stdout
+// or stderr. If logs are stored in a file they are rotated based on size and
+// there is a max of `max_logfiles_num` files saved.
+//
+// Returns the [`LoggingHandles`] that can be used to change the effective
+// log level and, when logging to a file, hot-swap the log file, without
+// having to restart the process.
+pub fn config_logging(args: Args) -> LoggingHandles {
+    let (filter, reload_handle) = reload::Layer::new(build_env_filter(
+        args.log_level,
+        args.log_filters.as_deref(),
+    ));
+
+    let (writer, ansi, file) = build_writer(
+        &args.log_destination,
+        args.max_logfiles_num,
+        args.max_size_in_mb,
     );
 
-    if !args.log_to_stdout {
-        tracing_subscriber::fmt()
-            .with_target(false)
-            .with_ansi(false)
-            .with_env_filter(filter)
-            .with_writer(Mutex::new(log_to_file))
-            .compact()
-            .init();
-    } else {
-        tracing_subscriber::fmt()
-            .with_target(false)
-            .with_env_filter(filter)
-            .compact()
-            .init();
+    match args.log_format {
+        LogFormat::Json => {
+            let fmt_layer = tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_ansi(ansi)
+                .with_writer(writer)
+                .json()
+                .flatten_event(true);
+            tracing_subscriber::registry()
+                .with(filter)
+                .with(fmt_layer)
+                .init();
+        }
+        LogFormat::Full => {
+            let fmt_layer = tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_ansi(ansi)
+                .with_writer(writer);
+            tracing_subscriber::registry()
+                .with(filter)
+                .with(fmt_layer)
+                .init();
+        }
+        LogFormat::Compact => {
+            let fmt_layer = tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_ansi(ansi)
+                .with_writer(writer)
+                .compact();
+            tracing_subscriber::registry()
+                .with(filter)
+                .with(fmt_layer)
+                .init();
+        }
+    }
+
+    LoggingHandles {
+        reload: reload_handle,
+        file,
     }
 }
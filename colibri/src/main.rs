@@ -1,5 +1,5 @@
 use axum::{http::Request, routing, Router};
-use database::DBHandler;
+use database::SessionStore;
 use glob::Pattern;
 use http::{request::Parts as RequestParts, HeaderValue};
 use log::{error, info};
@@ -7,7 +7,7 @@ use std::collections::HashSet;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpListener;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::Mutex;
 use tower_http::{
     cors::{AllowOrigin, CorsLayer},
     trace::TraceLayer,
@@ -22,34 +22,56 @@ mod database;
 mod globaldb;
 mod icons;
 mod logging;
+mod nft_metadata;
+mod types;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = args::parse_args();
-    logging::config_logging(args.clone());
+    let logging::LoggingHandles {
+        reload: log_reload_handle,
+        file: log_file_handle,
+    } = logging::config_logging(args.clone());
 
     info!("Starting colibri");
-    let globaldb =
-        match globaldb::GlobalDB::new(args.data_directory.join("global").join("global.db")).await {
-            Err(e) => {
-                error!("Unable to open globaldb due to {}", e);
-                std::process::exit(1);
-            }
-            Ok(globaldb) => Arc::new(globaldb),
-        };
+    let globaldb = match globaldb::GlobalDB::new(
+        args.data_directory.join("global").join("global.db"),
+        globaldb::DEFAULT_LOOKUP_CACHE_CAPACITY,
+    )
+    .await
+    {
+        Err(e) => {
+            error!("Unable to open globaldb due to {}", e);
+            std::process::exit(1);
+        }
+        Ok(globaldb) => Arc::new(globaldb),
+    };
     let coingecko = Arc::new(coingecko::Coingecko::new(
         globaldb.clone(),
         coingecko::COINGECKO_BASE_URL.to_string(),
     ));
     let evm_manager = Arc::new(EvmInquirerManager::new(globaldb.clone()));
     evm_manager.initialize_rpc_nodes().await;
+    let token_list_registry = Arc::new(icons::TokenListRegistry::new(args.token_list_urls.clone()));
+    token_list_registry.refresh().await;
+    let userdb = Arc::new(SessionStore::new());
+    tokio::spawn(database::session::run_idle_eviction(userdb.clone()));
+    let nft_metadata = Arc::new(nft_metadata::NftMetadata::new(
+        nft_metadata::NFT_METADATA_BASE_URL.to_string(),
+    ));
     let state = Arc::new(api::AppState {
         data_dir: args.data_directory,
         globaldb: globaldb.clone(),
         coingecko,
-        userdb: Arc::new(RwLock::new(DBHandler::new())),
+        userdb,
         active_tasks: Arc::new(Mutex::new(HashSet::<String>::new())),
-        evm_manager: Arc::new(EvmInquirerManager::new(globaldb.clone())),
+        evm_manager: evm_manager.clone(),
+        token_list_registry,
+        nft_metadata,
+        log_reload_handle,
+        log_file_handle,
+        max_logfiles_num: args.max_logfiles_num,
+        max_size_in_mb: args.max_size_in_mb,
     });
 
     let stateless_routes = Router::new().route("/health", routing::get(api::health::status));
@@ -57,10 +79,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/assets/icon", routing::get(api::icons::get_icon))
         .route("/assets/icon", routing::head(api::icons::check_icon))
         .route("/user", routing::post(api::database::unlock_user))
+        .route("/user/logout", routing::post(api::database::logout_user))
         .route(
             "/assets/ignored",
             routing::get(api::database::get_ignored_assets),
         )
+        .route("/logging/level", routing::post(api::logging::set_log_level))
+        .route("/logging/file", routing::post(api::logging::set_log_file))
+        .route("/nodes/health", routing::get(api::health::node_health))
+        .route("/globaldb/health", routing::get(api::health::globaldb_health))
         .with_state(state);
 
     let cors_patterns: Vec<Pattern> = args
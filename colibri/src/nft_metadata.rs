@@ -0,0 +1,168 @@
+use log::debug;
+use serde_json::Value;
+
+use crate::blockchain::{parse_asset_identifier, AssetAddress, SupportedBlockchain};
+use crate::database::user_db::NftData;
+use crate::icons::resolve_gateway_urls;
+
+/// Default Moralis-style NFT metadata indexer base URL.
+pub const NFT_METADATA_BASE_URL: &str = "https://deep-index.moralis.io/api/v2.2";
+
+/// Fetches NFT metadata (name, collection, image) from an external indexer
+/// for identifiers the user DB hasn't populated yet, mirroring `Coingecko`'s
+/// shape: a thin `reqwest` client over a configurable base URL so tests can
+/// point it at a mock server.
+pub struct NftMetadata {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl NftMetadata {
+    pub fn new(base_url: String) -> Self {
+        NftMetadata {
+            client: reqwest::Client::new(),
+            base_url,
+        }
+    }
+
+    /// Resolves `(chain, contract_address, token_id)` from the NFT
+    /// identifier and queries `/nft/{address}/{token_id}` for its metadata.
+    /// Returns `None` for anything that isn't an EVM NFT identifier on a
+    /// chain we recognize, or on any request/parse failure.
+    pub async fn fetch(&self, identifier: &str) -> Option<NftData> {
+        let asset_info = parse_asset_identifier(identifier)?;
+        let token_id = asset_info.token_id.clone()?;
+        let chain_id = asset_info.chain_id()?;
+        let blockchain = SupportedBlockchain::from_chain_id(chain_id)?;
+        let AssetAddress::Evm(address) = asset_info.contract_address else {
+            return None;
+        };
+
+        let url = format!("{}/nft/{:#x}/{}", self.base_url, address, token_id);
+        let response = match self
+            .client
+            .get(&url)
+            .query(&[("chain", blockchain.as_str().to_ascii_lowercase())])
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                debug!(
+                    "NFT metadata request for {} failed with status {}",
+                    identifier,
+                    response.status()
+                );
+                return None;
+            }
+            Err(e) => {
+                debug!("Failed to query NFT metadata for {} due to {}", identifier, e);
+                return None;
+            }
+        };
+
+        let body: Value = match response.json().await {
+            Ok(body) => body,
+            Err(e) => {
+                debug!("Failed to parse NFT metadata response for {}: {}", identifier, e);
+                return None;
+            }
+        };
+
+        parse_metadata_response(&body)
+    }
+}
+
+/// Parses a Moralis-style `/nft/{address}/{token_id}` response. `metadata`
+/// is the token-URI JSON, encoded as a string on both ERC-721 and ERC-1155
+/// responses (the `contract_type` field only matters for which other fields
+/// the indexer populates, not the shape of `metadata` itself).
+fn parse_metadata_response(body: &Value) -> Option<NftData> {
+    let name = body
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    let metadata = match body.get("metadata") {
+        Some(Value::String(raw)) => serde_json::from_str(raw).ok(),
+        Some(value @ Value::Object(_)) => Some(value.clone()),
+        _ => None,
+    };
+
+    let collection_name = body
+        .get("collection")
+        .or_else(|| body.get("name"))
+        .and_then(Value::as_str)
+        .map(|s| s.to_string());
+
+    let image_url = metadata
+        .as_ref()
+        .and_then(|m| m.get("image").or_else(|| m.get("image_url")))
+        .and_then(Value::as_str)
+        .map(|uri| {
+            resolve_gateway_urls(uri)
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| uri.to_string())
+        });
+
+    if name.is_empty() && collection_name.is_none() && image_url.is_none() {
+        return None;
+    }
+
+    Some(NftData {
+        name,
+        asset_type: "nft".to_string(),
+        collection_name,
+        image_url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_nft_metadata() {
+        let mut server = mockito::Server::new_async().await;
+        let identifier = "eip155:1/erc721:0xBC4CA0EdA7647A8aB7C2061c2E118A18a936f13D/1";
+
+        let _mock = server
+            .mock(
+                "GET",
+                "/nft/0xbc4ca0eda7647a8ab7c2061c2e118a18a936f13d/1",
+            )
+            .match_query(mockito::Matcher::UrlEncoded("chain".into(), "eth".into()))
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "name": "Cool Cats",
+                    "contract_type": "ERC721",
+                    "collection": "Cool Cats",
+                    "metadata": "{\"image\": \"ipfs://Qm123abc\"}"
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let nft_metadata = NftMetadata::new(server.url());
+        let data = nft_metadata.fetch(identifier).await.unwrap();
+
+        assert_eq!(data.name, "Cool Cats");
+        assert_eq!(data.collection_name, Some("Cool Cats".to_string()));
+        assert_eq!(
+            data.image_url,
+            Some("https://ipfs.io/ipfs/Qm123abc".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_nft_metadata_non_evm_returns_none() {
+        let nft_metadata = NftMetadata::new("https://example.com".to_string());
+        let result = nft_metadata
+            .fetch("solana/nft:7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU")
+            .await;
+        assert!(result.is_none());
+    }
+}
@@ -46,35 +46,15 @@ impl AssetType {
             return Err(format!("Failed to deserialize AssetType DB value {value}"));
         }
 
-        match number - 64 {
-            1 => Ok(AssetType::Fiat),
-            2 => Ok(AssetType::OwnChain),
-            3 => Ok(AssetType::EvmToken),
-            4 => Ok(AssetType::OmniToken),
-            5 => Ok(AssetType::NeoToken),
-            6 => Ok(AssetType::CounterpartyToken),
-            7 => Ok(AssetType::BitSharesToken),
-            8 => Ok(AssetType::ArdorToken),
-            9 => Ok(AssetType::NxtToken),
-            10 => Ok(AssetType::UbiqToken),
-            11 => Ok(AssetType::NuBitsToken),
-            12 => Ok(AssetType::BurstToken),
-            13 => Ok(AssetType::WavesToken),
-            14 => Ok(AssetType::QtumToken),
-            15 => Ok(AssetType::StellarToken),
-            16 => Ok(AssetType::TronToken),
-            17 => Ok(AssetType::OntologyToken),
-            18 => Ok(AssetType::VechainToken),
-            // 19 (Binance) was removed as it is EVM token
-            20 => Ok(AssetType::EosToken),
-            21 => Ok(AssetType::FusionToken),
-            22 => Ok(AssetType::LuniverseToken),
-            23 => Ok(AssetType::Other),
-            25 => Ok(AssetType::SolanaToken),
-            26 => Ok(AssetType::Nft),
-            27 => Ok(AssetType::CustomAsset),
-            _ => Err(format!("Failed to deserialize AssetType DB value {value}")),
-        }
+        Self::try_from(number - 64)
+            .map_err(|_| format!("Failed to deserialize AssetType DB value {value}"))
+    }
+
+    /// Inverse of `deserialize_from_db`: encodes the variant's discriminant
+    /// as the single ASCII character `deserialize_from_db` expects, i.e.
+    /// `char::from(self as u8 + 64)`.
+    pub fn serialize_to_db(self) -> char {
+        char::from(self as u8 + 64)
     }
 
     pub fn serialize(self) -> String {
@@ -106,84 +86,201 @@ impl AssetType {
             AssetType::CustomAsset => "custom asset".to_string(),
         }
     }
+
+    /// Inverse of `serialize`: parses the display string back into a variant.
+    pub fn from_serialized(value: &str) -> Result<Self, String> {
+        match value {
+            "fiat" => Ok(AssetType::Fiat),
+            "own chain" => Ok(AssetType::OwnChain),
+            "evm token" => Ok(AssetType::EvmToken),
+            "omni token" => Ok(AssetType::OmniToken),
+            "neo token" => Ok(AssetType::NeoToken),
+            "counterparty token" => Ok(AssetType::CounterpartyToken),
+            "bitshares token" => Ok(AssetType::BitSharesToken),
+            "ardor token" => Ok(AssetType::ArdorToken),
+            "nxt token" => Ok(AssetType::NxtToken),
+            "ubiq token" => Ok(AssetType::UbiqToken),
+            "nubits token" => Ok(AssetType::NuBitsToken),
+            "burst token" => Ok(AssetType::BurstToken),
+            "waves token" => Ok(AssetType::WavesToken),
+            "qtum token" => Ok(AssetType::QtumToken),
+            "stellar token" => Ok(AssetType::StellarToken),
+            "tron token" => Ok(AssetType::TronToken),
+            "ontology token" => Ok(AssetType::OntologyToken),
+            "vechain token" => Ok(AssetType::VechainToken),
+            "eos token" => Ok(AssetType::EosToken),
+            "fusion token" => Ok(AssetType::FusionToken),
+            "luniverse token" => Ok(AssetType::LuniverseToken),
+            "other" => Ok(AssetType::Other),
+            "solana token" => Ok(AssetType::SolanaToken),
+            "nft" => Ok(AssetType::Nft),
+            "custom asset" => Ok(AssetType::CustomAsset),
+            _ => Err(format!("Failed to deserialize AssetType value {value}")),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[repr(u32)]
-pub enum ChainID {
-    Ethereum = 1,
-    Optimism = 10,
-    BinanceSc = 56,
-    Gnosis = 100,
-    PolygonPos = 137,
-    Fantom = 250,
-    Base = 8453,
-    ArbitrumOne = 42161,
-    Avalanche = 43114,
-    Celo = 42220,
-    ArbitrumNova = 42170,
-    Cronos = 25,
-    Boba = 288,
-    Evmos = 9001,
-    PolygonZkevm = 1101,
-    ZksyncEra = 324,
-    Pulsechain = 369,
-    Scroll = 534352,
-    Sonic = 146,
-    Linea = 59144,
+impl std::str::FromStr for AssetType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::from_serialized(value)
+    }
 }
 
-impl ChainID {
-    pub fn deserialize_from_db(value: u32) -> Result<Self, String> {
+impl TryFrom<u32> for AssetType {
+    type Error = String;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
         match value {
-            1 => Ok(ChainID::Ethereum),
-            10 => Ok(ChainID::Optimism),
-            56 => Ok(ChainID::BinanceSc),
-            100 => Ok(ChainID::Gnosis),
-            137 => Ok(ChainID::PolygonPos),
-            250 => Ok(ChainID::Fantom),
-            8453 => Ok(ChainID::Base),
-            42161 => Ok(ChainID::ArbitrumOne),
-            43114 => Ok(ChainID::Avalanche),
-            42220 => Ok(ChainID::Celo),
-            42170 => Ok(ChainID::ArbitrumNova),
-            25 => Ok(ChainID::Cronos),
-            288 => Ok(ChainID::Boba),
-            9001 => Ok(ChainID::Evmos),
-            1101 => Ok(ChainID::PolygonZkevm),
-            324 => Ok(ChainID::ZksyncEra),
-            369 => Ok(ChainID::Pulsechain),
-            534352 => Ok(ChainID::Scroll),
-            146 => Ok(ChainID::Sonic),
-            59144 => Ok(ChainID::Linea),
-            _ => Err(format!("Unknown chain ID: {value}")),
+            1 => Ok(AssetType::Fiat),
+            2 => Ok(AssetType::OwnChain),
+            3 => Ok(AssetType::EvmToken),
+            4 => Ok(AssetType::OmniToken),
+            5 => Ok(AssetType::NeoToken),
+            6 => Ok(AssetType::CounterpartyToken),
+            7 => Ok(AssetType::BitSharesToken),
+            8 => Ok(AssetType::ArdorToken),
+            9 => Ok(AssetType::NxtToken),
+            10 => Ok(AssetType::UbiqToken),
+            11 => Ok(AssetType::NuBitsToken),
+            12 => Ok(AssetType::BurstToken),
+            13 => Ok(AssetType::WavesToken),
+            14 => Ok(AssetType::QtumToken),
+            15 => Ok(AssetType::StellarToken),
+            16 => Ok(AssetType::TronToken),
+            17 => Ok(AssetType::OntologyToken),
+            18 => Ok(AssetType::VechainToken),
+            // 19 (Binance) was removed as it is EVM token
+            20 => Ok(AssetType::EosToken),
+            21 => Ok(AssetType::FusionToken),
+            22 => Ok(AssetType::LuniverseToken),
+            23 => Ok(AssetType::Other),
+            25 => Ok(AssetType::SolanaToken),
+            26 => Ok(AssetType::Nft),
+            27 => Ok(AssetType::CustomAsset),
+            _ => Err(format!("Unknown AssetType discriminant: {value}")),
         }
     }
+}
 
-    pub fn to_name(self) -> String {
+/// Static metadata for one EVM chain, as loaded from the bundled
+/// `chains.json` registry -- see `ChainID`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ChainSpec {
+    pub id: u64,
+    pub name: String,
+    pub short_name: String,
+    pub native_currency_decimals: u8,
+    #[serde(default)]
+    pub default_rpc_nodes: Vec<String>,
+}
+
+const CHAINS_JSON: &str = include_str!("chains.json");
+
+fn registry() -> &'static std::collections::HashMap<u64, ChainSpec> {
+    static REGISTRY: std::sync::OnceLock<std::collections::HashMap<u64, ChainSpec>> =
+        std::sync::OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let chains: Vec<ChainSpec> =
+            serde_json::from_str(CHAINS_JSON).expect("bundled chains.json must parse");
+        chains.into_iter().map(|spec| (spec.id, spec)).collect()
+    })
+}
+
+/// An EVM chain id, backed by the data-driven registry loaded from the
+/// bundled `chains.json` rather than a hand-maintained match statement --
+/// adding a new chain only requires adding a row to that file. `Known` ids
+/// have a `ChainSpec` in the registry; `Unknown` is any other syntactically
+/// valid EIP-155 chain id, so callers like `parse_asset_identifier` never
+/// have to silently drop a chain just because it isn't one of rotki's
+/// well-known ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChainID {
+    Known(u64),
+    Unknown(u64),
+}
+
+impl ChainID {
+    pub const ETHEREUM: ChainID = ChainID::Known(1);
+    pub const OPTIMISM: ChainID = ChainID::Known(10);
+    pub const BINANCE_SC: ChainID = ChainID::Known(56);
+    pub const GNOSIS: ChainID = ChainID::Known(100);
+    pub const POLYGON_POS: ChainID = ChainID::Known(137);
+    pub const FANTOM: ChainID = ChainID::Known(250);
+    pub const BASE: ChainID = ChainID::Known(8453);
+    pub const ARBITRUM_ONE: ChainID = ChainID::Known(42161);
+    pub const AVALANCHE: ChainID = ChainID::Known(43114);
+    pub const CELO: ChainID = ChainID::Known(42220);
+    pub const ARBITRUM_NOVA: ChainID = ChainID::Known(42170);
+    pub const CRONOS: ChainID = ChainID::Known(25);
+    pub const BOBA: ChainID = ChainID::Known(288);
+    pub const EVMOS: ChainID = ChainID::Known(9001);
+    pub const POLYGON_ZKEVM: ChainID = ChainID::Known(1101);
+    pub const ZKSYNC_ERA: ChainID = ChainID::Known(324);
+    pub const PULSECHAIN: ChainID = ChainID::Known(369);
+    pub const SCROLL: ChainID = ChainID::Known(534352);
+    pub const SONIC: ChainID = ChainID::Known(146);
+    pub const LINEA: ChainID = ChainID::Known(59144);
+
+    /// The numeric EIP-155 chain id, regardless of whether it's in the registry.
+    pub fn id(self) -> u64 {
         match self {
-            ChainID::Ethereum => "ethereum".to_string(),
-            ChainID::Optimism => "optimism".to_string(),
-            ChainID::BinanceSc => "binance_sc".to_string(),
-            ChainID::Gnosis => "gnosis".to_string(),
-            ChainID::PolygonPos => "polygon_pos".to_string(),
-            ChainID::Fantom => "fantom".to_string(),
-            ChainID::Base => "base".to_string(),
-            ChainID::ArbitrumOne => "arbitrum_one".to_string(),
-            ChainID::Avalanche => "avalanche".to_string(),
-            ChainID::Celo => "celo".to_string(),
-            ChainID::ArbitrumNova => "arbitrum_nova".to_string(),
-            ChainID::Cronos => "cronos".to_string(),
-            ChainID::Boba => "boba".to_string(),
-            ChainID::Evmos => "evmos".to_string(),
-            ChainID::PolygonZkevm => "polygon_zkevm".to_string(),
-            ChainID::ZksyncEra => "zksync_era".to_string(),
-            ChainID::Pulsechain => "pulsechain".to_string(),
-            ChainID::Scroll => "scroll".to_string(),
-            ChainID::Sonic => "sonic".to_string(),
-            ChainID::Linea => "linea".to_string(),
+            ChainID::Known(id) | ChainID::Unknown(id) => id,
         }
     }
+
+    /// Builds a `ChainID` from any numeric chain id, looking it up in the
+    /// registry to decide between `Known` and `Unknown`.
+    pub fn new(id: u64) -> Self {
+        if registry().contains_key(&id) {
+            ChainID::Known(id)
+        } else {
+            ChainID::Unknown(id)
+        }
+    }
+
+    /// Looks up the bundled metadata for a chain id, if any.
+    pub fn get(id: u64) -> Option<&'static ChainSpec> {
+        registry().get(&id)
+    }
+
+    /// This chain's own bundled metadata, if it's in the registry.
+    pub fn spec(self) -> Option<&'static ChainSpec> {
+        Self::get(self.id())
+    }
+
+    /// Looks up a chain by its canonical name (e.g. `"polygon_pos"`).
+    pub fn by_name(name: &str) -> Option<&'static ChainSpec> {
+        registry().values().find(|spec| spec.name == name)
+    }
+
+    pub fn deserialize_from_db(value: u32) -> Self {
+        Self::new(value as u64)
+    }
+
+    /// Maps an EIP-3770 `shortName` (the prefix in a `shortName:address`
+    /// chain-prefixed address, e.g. `eth:0x...`) to the chain it identifies.
+    pub fn from_short_name(name: &str) -> Option<Self> {
+        registry()
+            .values()
+            .find(|spec| spec.short_name == name)
+            .map(|spec| ChainID::Known(spec.id))
+    }
+
+    /// Inverse of `from_short_name`. Falls back to the numeric id for
+    /// `Unknown` chains, which have no registered short name.
+    pub fn to_short_name(self) -> String {
+        self.spec()
+            .map(|spec| spec.short_name.clone())
+            .unwrap_or_else(|| self.id().to_string())
+    }
+
+    pub fn to_name(self) -> String {
+        self.spec()
+            .map(|spec| spec.name.clone())
+            .unwrap_or_else(|| format!("unknown_chain_{}", self.id()))
+    }
 }
 
 #[cfg(test)]
@@ -199,19 +296,128 @@ mod tests {
         assert!(AssetType::deserialize_from_db("@").is_err());  // ASCII 64, too low
     }
 
+    #[test]
+    fn test_asset_type_round_trip() {
+        const ALL_VARIANTS: [AssetType; 25] = [
+            AssetType::Fiat,
+            AssetType::OwnChain,
+            AssetType::EvmToken,
+            AssetType::OmniToken,
+            AssetType::NeoToken,
+            AssetType::CounterpartyToken,
+            AssetType::BitSharesToken,
+            AssetType::ArdorToken,
+            AssetType::NxtToken,
+            AssetType::UbiqToken,
+            AssetType::NuBitsToken,
+            AssetType::BurstToken,
+            AssetType::WavesToken,
+            AssetType::QtumToken,
+            AssetType::StellarToken,
+            AssetType::TronToken,
+            AssetType::OntologyToken,
+            AssetType::VechainToken,
+            AssetType::EosToken,
+            AssetType::FusionToken,
+            AssetType::LuniverseToken,
+            AssetType::Other,
+            AssetType::SolanaToken,
+            AssetType::Nft,
+            AssetType::CustomAsset,
+        ];
+
+        for variant in ALL_VARIANTS {
+            let db_value = variant.serialize_to_db().to_string();
+            assert_eq!(
+                AssetType::deserialize_from_db(&db_value).unwrap(),
+                variant
+            );
+
+            let serialized = variant.serialize();
+            assert_eq!(AssetType::from_serialized(&serialized).unwrap(), variant);
+            assert_eq!(serialized.parse::<AssetType>().unwrap(), variant);
+
+            assert_eq!(AssetType::try_from(variant as u32).unwrap(), variant);
+        }
+    }
+
     #[test]
     fn test_chain_id_deserialization() {
-        assert_eq!(ChainID::deserialize_from_db(1).unwrap(), ChainID::Ethereum);
-        assert_eq!(ChainID::deserialize_from_db(10).unwrap(), ChainID::Optimism);
-        assert_eq!(ChainID::deserialize_from_db(42161).unwrap(), ChainID::ArbitrumOne);
-        assert!(ChainID::deserialize_from_db(999999).is_err());
+        assert_eq!(ChainID::deserialize_from_db(1), ChainID::ETHEREUM);
+        assert_eq!(ChainID::deserialize_from_db(10), ChainID::OPTIMISM);
+        assert_eq!(ChainID::deserialize_from_db(42161), ChainID::ARBITRUM_ONE);
+        // Not in the bundled registry, but still a syntactically valid chain id
+        assert_eq!(ChainID::deserialize_from_db(999999), ChainID::Unknown(999999));
+    }
+
+    /// Every chain that used to be a hardcoded `ChainID` enum variant must
+    /// still be present in the bundled registry with a matching id and name.
+    #[test]
+    fn test_bundled_chains_json_matches_hardcoded_chains() {
+        const EXPECTED: [(ChainID, &str, &str); 20] = [
+            (ChainID::ETHEREUM, "ethereum", "eth"),
+            (ChainID::OPTIMISM, "optimism", "oeth"),
+            (ChainID::BINANCE_SC, "binance_sc", "bnb"),
+            (ChainID::GNOSIS, "gnosis", "gno"),
+            (ChainID::POLYGON_POS, "polygon_pos", "matic"),
+            (ChainID::FANTOM, "fantom", "ftm"),
+            (ChainID::BASE, "base", "base"),
+            (ChainID::ARBITRUM_ONE, "arbitrum_one", "arb1"),
+            (ChainID::AVALANCHE, "avalanche", "avax"),
+            (ChainID::CELO, "celo", "celo"),
+            (ChainID::ARBITRUM_NOVA, "arbitrum_nova", "arb-nova"),
+            (ChainID::CRONOS, "cronos", "cro"),
+            (ChainID::BOBA, "boba", "boba"),
+            (ChainID::EVMOS, "evmos", "evmos"),
+            (ChainID::POLYGON_ZKEVM, "polygon_zkevm", "zkevm"),
+            (ChainID::ZKSYNC_ERA, "zksync_era", "zksync"),
+            (ChainID::PULSECHAIN, "pulsechain", "pls"),
+            (ChainID::SCROLL, "scroll", "scr"),
+            (ChainID::SONIC, "sonic", "sonic"),
+            (ChainID::LINEA, "linea", "linea"),
+        ];
+
+        assert_eq!(registry().len(), EXPECTED.len());
+
+        for (chain, name, short_name) in EXPECTED {
+            let spec = chain.spec().unwrap_or_else(|| {
+                panic!("chain id {} missing from bundled chains.json", chain.id())
+            });
+            assert_eq!(spec.id, chain.id());
+            assert_eq!(spec.name, name);
+            assert_eq!(spec.short_name, short_name);
+        }
+    }
+
+    #[test]
+    fn test_chain_id_short_name_round_trip() {
+        for spec in registry().values() {
+            let chain = ChainID::Known(spec.id);
+            assert_eq!(chain.to_short_name(), spec.short_name);
+            assert_eq!(ChainID::from_short_name(&spec.short_name), Some(chain));
+        }
+
+        assert_eq!(ChainID::from_short_name("not-a-chain"), None);
+
+        // An unregistered chain still round-trips through its numeric id
+        let unknown = ChainID::Unknown(7777777);
+        assert_eq!(unknown.to_short_name(), "7777777");
+        assert_eq!(unknown.to_name(), "unknown_chain_7777777");
     }
 
     #[test]
     fn test_chain_id_to_name() {
-        assert_eq!(ChainID::Ethereum.to_name(), "ethereum");
-        assert_eq!(ChainID::Optimism.to_name(), "optimism");
-        assert_eq!(ChainID::ArbitrumOne.to_name(), "arbitrum_one");
-        assert_eq!(ChainID::PolygonPos.to_name(), "polygon_pos");
+        assert_eq!(ChainID::ETHEREUM.to_name(), "ethereum");
+        assert_eq!(ChainID::OPTIMISM.to_name(), "optimism");
+        assert_eq!(ChainID::ARBITRUM_ONE.to_name(), "arbitrum_one");
+        assert_eq!(ChainID::POLYGON_POS.to_name(), "polygon_pos");
+    }
+
+    #[test]
+    fn test_chain_id_by_name_and_get() {
+        assert_eq!(ChainID::by_name("polygon_pos").unwrap().id, 137);
+        assert!(ChainID::by_name("not-a-chain").is_none());
+        assert_eq!(ChainID::get(1).unwrap().name, "ethereum");
+        assert!(ChainID::get(999999).is_none());
     }
 }
@@ -1,129 +1,425 @@
+use clap::{Arg, ArgAction, Command};
 use regex::Regex;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::io::{self, BufRead, BufReader};
+use std::io;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
-#[derive(Debug, Clone)]
+/// Whether a scanned literal was a `bytes` or `str` constant. Kept distinct
+/// in the duplicate-value map so a `b'foo'` and a `'foo'` -- different
+/// Python objects that happen to share the same underlying bytes -- are
+/// never reported as duplicates of each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum LiteralKind {
+    Bytes,
+    Str,
+}
+
+#[derive(Debug, Clone, Serialize)]
 struct ConstantLocation {
     file: PathBuf,
     line: usize,
     name: String,
+    kind: LiteralKind,
+    #[serde(skip)]
     value: Vec<u8>,
 }
 
-fn parse_byte_literal(s: &str) -> Option<Vec<u8>> {
-    // Remove the b' prefix and ' suffix
-    if !s.starts_with("b'") || !s.ends_with('\'') {
-        return None;
-    }
+/// One group of constants sharing the same value and kind, as emitted in
+/// the `--format json` report.
+#[derive(Debug, Serialize)]
+struct DuplicateGroup<'a> {
+    kind: LiteralKind,
+    hex_value: String,
+    locations: &'a [ConstantLocation],
+    /// Whether this group's baseline key was already present in the
+    /// `--baseline` file, i.e. this duplicate is pre-existing rather than
+    /// newly introduced.
+    baselined: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Report<'a> {
+    scanned_files: usize,
+    scanned_constants: usize,
+    duplicate_groups: Vec<DuplicateGroup<'a>>,
+    new_duplicate_groups: usize,
+}
 
-    let content = &s[2..s.len() - 1];
+/// On-disk shape of the `--baseline`/`--write-baseline` file: the
+/// `baseline_key` (`<kind>:<hex value>`) of every duplicate group that's
+/// been reviewed and accepted, so later runs
+/// only fail the build on duplicates that weren't already there.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Baseline {
+    accepted_hex_values: Vec<String>,
+}
+
+fn load_baseline(path: &Path) -> io::Result<HashSet<String>> {
+    let contents = fs::read_to_string(path)?;
+    let baseline: Baseline = serde_json::from_str(&contents)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    Ok(baseline.accepted_hex_values.into_iter().collect())
+}
+
+fn write_baseline(path: &Path, hex_values: Vec<String>) -> io::Result<()> {
+    let mut hex_values = hex_values;
+    hex_values.sort();
+    let baseline = Baseline {
+        accepted_hex_values: hex_values,
+    };
+    let json = serde_json::to_string_pretty(&baseline)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(path, json)
+}
+
+/// Pushes `ch` onto `out` as UTF-8 bytes (1 byte for ASCII, up to 4 otherwise).
+fn push_char_utf8(out: &mut Vec<u8>, ch: char) {
+    let mut buf = [0; 4];
+    out.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+}
+
+/// Best-effort resolution of a small set of `\N{NAME}` escapes commonly seen
+/// in this codebase. Full resolution needs the Unicode names database, which
+/// isn't vendored here -- an unrecognized name falls back to the literal
+/// `\N{NAME}` text so it still hashes consistently rather than being dropped.
+fn resolve_unicode_name(name: &str) -> Option<char> {
+    Some(match name {
+        "BULLET" => '\u{2022}',
+        "EM DASH" => '\u{2014}',
+        "EN DASH" => '\u{2013}',
+        "DEGREE SIGN" => '\u{00B0}',
+        "NO-BREAK SPACE" => '\u{00A0}',
+        "COPYRIGHT SIGN" => '\u{00A9}',
+        "REGISTERED SIGN" => '\u{00AE}',
+        _ => return None,
+    })
+}
+
+/// Decodes the body of a (non-raw) literal: `\x` and octal escapes become
+/// the raw byte, `\n`/`\r`/`\t` their control byte, and `\uXXXX`,
+/// `\U00XXXXXX` and `\N{NAME}` the UTF-8 encoding of the code point. The
+/// same rules apply to `bytes` and `str` literals.
+fn decode_escapes(content: &str) -> Vec<u8> {
     let mut result = Vec::new();
-    let mut chars = content.chars();
+    let mut chars = content.chars().peekable();
 
     while let Some(ch) = chars.next() {
-        if ch == '\\' {
-            if let Some(next) = chars.next() {
-                match next {
-                    'x' => {
-                        // Parse hex escape
-                        let mut hex = String::new();
-                        for _ in 0..2 {
-                            if let Some(h) = chars.next() {
-                                hex.push(h);
-                            } else {
-                                return None;
-                            }
-                        }
-                        if let Ok(byte) = u8::from_str_radix(&hex, 16) {
-                            result.push(byte);
-                        } else {
-                            return None;
-                        }
+        if ch != '\\' {
+            push_char_utf8(&mut result, ch);
+            continue;
+        }
+        let Some(next) = chars.next() else {
+            result.push(b'\\');
+            break;
+        };
+        match next {
+            'x' => {
+                let hex: String = (0..2).filter_map(|_| chars.next()).collect();
+                match u8::from_str_radix(&hex, 16) {
+                    Ok(byte) => result.push(byte),
+                    Err(_) => push_char_utf8(&mut result, next),
+                }
+            }
+            'u' => {
+                let hex: String = (0..4).filter_map(|_| chars.next()).collect();
+                if let Some(c) = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    push_char_utf8(&mut result, c);
+                }
+            }
+            'U' => {
+                let hex: String = (0..8).filter_map(|_| chars.next()).collect();
+                if let Some(c) = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    push_char_utf8(&mut result, c);
+                }
+            }
+            'N' if chars.peek() == Some(&'{') => {
+                chars.next(); // consume '{'
+                let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                match resolve_unicode_name(&name) {
+                    Some(c) => push_char_utf8(&mut result, c),
+                    None => {
+                        result.extend_from_slice(format!("\\N{{{}}}", name).as_bytes());
                     }
-                    'n' => result.push(b'\n'),
-                    'r' => result.push(b'\r'),
-                    't' => result.push(b'\t'),
-                    '\\' => result.push(b'\\'),
-                    '\'' => result.push(b'\''),
-                    '"' => result.push(b'"'),
-                    _ => {
-                        // For other escapes, try to parse as octal or just use the char
-                        if next.is_ascii_digit() {
-                            // Octal escape
-                            let mut octal = String::from(next);
-                            for _ in 0..2 {
-                                if let Some(o) = chars.next() {
-                                    if o.is_ascii_digit() {
-                                        octal.push(o);
-                                    } else {
-                                        // Put it back by processing it next iteration
-                                        result.push(next as u8);
-                                        break;
-                                    }
-                                }
-                            }
-                            if let Ok(byte) = u8::from_str_radix(&octal, 8) {
-                                result.push(byte);
-                            }
-                        } else {
-                            // Unknown escape, just use the character
-                            result.push(next as u8);
+                }
+            }
+            'n' => result.push(b'\n'),
+            'r' => result.push(b'\r'),
+            't' => result.push(b'\t'),
+            '\\' => result.push(b'\\'),
+            '\'' => result.push(b'\''),
+            '"' => result.push(b'"'),
+            '0'..='7' => {
+                let mut octal = String::from(next);
+                while octal.len() < 3 {
+                    match chars.peek() {
+                        Some(&c) if c.is_digit(8) => {
+                            octal.push(c);
+                            chars.next();
                         }
+                        _ => break,
                     }
                 }
+                if let Ok(byte) = u8::from_str_radix(&octal, 8) {
+                    result.push(byte);
+                }
             }
-        } else {
-            // Regular character
-            if ch.is_ascii() {
-                result.push(ch as u8);
-            } else {
-                // Non-ASCII character, encode as UTF-8
-                let mut buf = [0; 4];
-                let s = ch.encode_utf8(&mut buf);
-                result.extend_from_slice(s.as_bytes());
+            other => push_char_utf8(&mut result, other),
+        }
+    }
+
+    result
+}
+
+/// One literal token parsed from source: its prefix-derived kind, its decoded
+/// value, and the byte offset just past it so the caller can keep scanning.
+struct ParsedLiteral {
+    kind: LiteralKind,
+    value: Vec<u8>,
+    end: usize,
+}
+
+/// Parses a single possibly-prefixed, possibly-triple-quoted Python literal
+/// starting at `text[start..]`, returning its decoded value and the byte
+/// offset just past its closing quote. Returns `None` if `start` isn't the
+/// beginning of a literal.
+fn parse_one_literal(text: &str, start: usize) -> Option<ParsedLiteral> {
+    let rest = &text[start..];
+    let mut prefix_len = 0;
+    let mut is_bytes = false;
+    let mut is_raw = false;
+    for ch in rest.chars().take(2) {
+        match ch.to_ascii_lowercase() {
+            'b' if !is_bytes => {
+                is_bytes = true;
+                prefix_len += ch.len_utf8();
+            }
+            'r' if !is_raw => {
+                is_raw = true;
+                prefix_len += ch.len_utf8();
+            }
+            'u' if !is_bytes && !is_raw && prefix_len == 0 => {
+                prefix_len += ch.len_utf8();
+            }
+            _ => break,
+        }
+    }
+
+    let after_prefix = &rest[prefix_len..];
+    let (quote_char, quote_len) = if after_prefix.starts_with("'''") {
+        ('\'', 3)
+    } else if after_prefix.starts_with("\"\"\"") {
+        ('"', 3)
+    } else if let Some(c) = after_prefix.chars().next().filter(|&c| c == '\'' || c == '"') {
+        (c, 1)
+    } else {
+        return None;
+    };
+
+    let closing = quote_char.to_string().repeat(quote_len);
+    let body_start = prefix_len + quote_len;
+    let body = &rest[body_start..];
+
+    // Find the closing delimiter, respecting backslash-escapes (a raw
+    // string's trailing backslash still protects the quote from Python's
+    // point of view, so this applies regardless of the raw flag).
+    let mut escaped = false;
+    let mut body_end = None;
+    let body_chars: Vec<(usize, char)> = body.char_indices().collect();
+    let mut i = 0;
+    while i < body_chars.len() {
+        let (byte_idx, ch) = body_chars[i];
+        if escaped {
+            escaped = false;
+            i += 1;
+            continue;
+        }
+        if ch == '\\' {
+            escaped = true;
+            i += 1;
+            continue;
+        }
+        if body[byte_idx..].starts_with(&closing) {
+            body_end = Some(byte_idx);
+            break;
+        }
+        i += 1;
+    }
+    let body_end = body_end?;
+    let content = &body[..body_end];
+
+    let value = if is_raw {
+        // A raw literal's backslashes are kept literally (no escape
+        // processing), but non-ASCII source is still UTF-8 re-encoded.
+        let mut out = Vec::new();
+        for ch in content.chars() {
+            push_char_utf8(&mut out, ch);
+        }
+        out
+    } else {
+        decode_escapes(content)
+    };
+
+    Some(ParsedLiteral {
+        kind: if is_bytes { LiteralKind::Bytes } else { LiteralKind::Str },
+        value,
+        end: start + body_start + body_end + closing.len(),
+    })
+}
+
+/// Skips horizontal whitespace and, at most, a single `\`-continued or bare
+/// newline (but not a second, blank-line-indicating one) starting at
+/// `text[pos..]`, returning the offset just past it.
+fn skip_inter_literal_gap(text: &str, mut pos: usize) -> Option<usize> {
+    let skip_horizontal = |text: &str, mut pos: usize| {
+        while text[pos..].starts_with(' ') || text[pos..].starts_with('\t') {
+            pos += 1;
+        }
+        pos
+    };
+
+    pos = skip_horizontal(text, pos);
+    if let Some(rest) = text[pos..].strip_prefix('\\') {
+        if let Some(after_newline) = rest.strip_prefix('\n') {
+            pos = text.len() - after_newline.len();
+            pos = skip_horizontal(text, pos);
+            return Some(pos);
+        }
+    }
+    if let Some(rest) = text[pos..].strip_prefix('\n') {
+        if rest.starts_with('\n') {
+            return None; // blank line: end of this statement's literal sequence
+        }
+        pos = text.len() - rest.len();
+        pos = skip_horizontal(text, pos);
+        return Some(pos);
+    }
+    Some(pos)
+}
+
+/// Parses one or more implicitly-concatenated literals of the same kind
+/// starting at `text[start..]` (e.g. `b'ab' b'cd'`), concatenating their
+/// values into one. Adjacent literals may be separated by whitespace,
+/// including a single line break (covering backslash-continued lines; a
+/// right-hand side that opens with `(` is not parsed at all), but a blank
+/// line ends the sequence, to avoid accidentally
+/// pulling in an unrelated literal from a later statement.
+fn parse_literal_sequence(text: &str, start: usize) -> Option<(LiteralKind, Vec<u8>, usize)> {
+    let first = parse_one_literal(text, start)?;
+    let kind = first.kind;
+    let mut value = first.value;
+    let mut pos = first.end;
+
+    while let Some(next_start) = skip_inter_literal_gap(text, pos) {
+        match parse_one_literal(text, next_start) {
+            Some(next) if next.kind == kind => {
+                value.extend(next.value);
+                pos = next.end;
             }
+            _ => break,
         }
     }
 
-    Some(result)
+    Some((kind, value, pos))
+}
+
+fn line_number_at(text: &str, byte_offset: usize) -> usize {
+    text[..byte_offset].matches('\n').count() + 1
 }
 
-fn find_constants_in_file(path: &Path) -> io::Result<Vec<ConstantLocation>> {
-    let file = fs::File::open(path)?;
-    let reader = BufReader::new(file);
+fn find_constants_in_file(path: &Path, include_str: bool) -> io::Result<Vec<ConstantLocation>> {
+    let text = fs::read_to_string(path)?;
     let mut constants = Vec::new();
 
-    // Regex to match constant definitions with byte literals
-    // Matches patterns like: CONSTANT_NAME: Final = b'...', CONSTANT_NAME: bytes = b'...', or CONSTANT_NAME = b'...'
-    // The type annotation (: Final, : bytes, etc.) is completely optional
-    let re =
-        Regex::new(r"^\s*([A-Z_][A-Z0-9_]*)\s*(?::\s*[A-Za-z_][A-Za-z0-9_\[\]]*(?:\[[^\]]*\])?)?\s*=\s*(b'(?:[^'\\]|\\.)*')").unwrap();
-
-    for (line_num, line) in reader.lines().enumerate() {
-        let line = line?;
-
-        if let Some(captures) = re.captures(&line) {
-            let name = captures.get(1).unwrap().as_str().to_string();
-            let byte_literal = captures.get(2).unwrap().as_str();
-
-            if let Some(bytes) = parse_byte_literal(byte_literal) {
-                constants.push(ConstantLocation {
-                    file: path.to_path_buf(),
-                    line: line_num + 1,
-                    name,
-                    value: bytes,
-                });
-            }
+    // Matches the start of an assignment: `NAME[: type] = `. The type
+    // annotation (`: Final`, `: bytes`, etc.) is completely optional. Only
+    // the assignment header is matched here; the literal(s) on the RHS are
+    // parsed manually by `parse_literal_sequence` so triple-quoted and
+    // implicitly-concatenated literals (which can span lines) are handled.
+    let re = Regex::new(
+        r"(?m)^\s*([A-Z_][A-Z0-9_]*)\s*(?::\s*[A-Za-z_][A-Za-z0-9_\[\]]*(?:\[[^\]]*\])?)?\s*=\s*",
+    )
+    .unwrap();
+
+    for captures in re.captures_iter(&text) {
+        let whole = captures.get(0).unwrap();
+        let name = captures.get(1).unwrap().as_str().to_string();
+
+        let Some((kind, value, _end)) = parse_literal_sequence(&text, whole.end()) else {
+            continue;
+        };
+        if kind == LiteralKind::Str && !include_str {
+            continue;
         }
+
+        constants.push(ConstantLocation {
+            file: path.to_path_buf(),
+            line: line_number_at(&text, whole.start()),
+            name,
+            kind,
+            value,
+        });
     }
 
     Ok(constants)
 }
 
+fn hex_of(value: &[u8]) -> String {
+    value.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Key used in the baseline file: distinguishes a `bytes` and a `str`
+/// constant that happen to share the same underlying hex value.
+fn baseline_key(kind: LiteralKind, hex_value: &str) -> String {
+    let kind_label = match kind {
+        LiteralKind::Bytes => "bytes",
+        LiteralKind::Str => "str",
+    };
+    format!("{}:{}", kind_label, hex_value)
+}
+
+fn parse_args() -> clap::ArgMatches {
+    Command::new("find-duplicate-constants")
+        .about("Finds Python byte-literal constants defined with the same value in more than one place")
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .help("Report format: human-readable text, or a machine-readable JSON document for CI"),
+        )
+        .arg(
+            Arg::new("baseline")
+                .long("baseline")
+                .value_name("PATH")
+                .help("Path to a baseline file of already-accepted duplicate hex values; only duplicates outside it fail the build"),
+        )
+        .arg(
+            Arg::new("write-baseline")
+                .long("write-baseline")
+                .action(ArgAction::SetTrue)
+                .requires("baseline")
+                .help("Write the current duplicate groups to --baseline instead of checking against it"),
+        )
+        .arg(
+            Arg::new("include-str")
+                .long("include-str")
+                .action(ArgAction::SetTrue)
+                .help("Also scan plain `str` constants, not just `bytes`, for duplicated values"),
+        )
+        .get_matches()
+}
+
 fn main() -> io::Result<()> {
+    let matches = parse_args();
+    let format = matches.get_one::<String>("format").map(String::as_str).unwrap_or("text");
+    let baseline_path = matches.get_one::<String>("baseline").map(PathBuf::from);
+    let write_baseline_flag = matches.get_flag("write-baseline");
+    let include_str = matches.get_flag("include-str");
+
     let mut all_constants = Vec::new();
     let mut file_count = 0;
     let mut constant_count = 0;
@@ -167,7 +463,7 @@ fn main() -> io::Result<()> {
             continue;
         }
 
-        match find_constants_in_file(path) {
+        match find_constants_in_file(path, include_str) {
             Ok(constants) => {
                 if !constants.is_empty() {
                     file_count += 1;
@@ -181,60 +477,107 @@ fn main() -> io::Result<()> {
         }
     }
 
-    println!(
-        "Scanned {} files, found {} byte constants",
-        file_count, constant_count
-    );
-    println!("Analyzing for duplicates...\n");
-
-    // Group constants by their byte value
-    let mut value_map: HashMap<Vec<u8>, Vec<ConstantLocation>> = HashMap::new();
-
+    // Group constants by their (kind, value) -- a `bytes` and a `str`
+    // constant sharing the same underlying bytes are not duplicates of
+    // each other.
+    let mut value_map: HashMap<(LiteralKind, Vec<u8>), Vec<ConstantLocation>> = HashMap::new();
     for constant in all_constants {
         value_map
-            .entry(constant.value.clone())
-            .or_insert_with(Vec::new)
+            .entry((constant.kind, constant.value.clone()))
+            .or_default()
             .push(constant);
     }
 
-    // Find and report duplicates
-    let mut duplicates_found = false;
-    let mut duplicate_groups = 0;
-    let mut total_duplicates = 0;
+    let duplicate_values: Vec<((LiteralKind, Vec<u8>), Vec<ConstantLocation>)> = value_map
+        .into_iter()
+        .filter(|(_, locations)| locations.len() > 1)
+        .collect();
+
+    if write_baseline_flag {
+        let baseline_path = baseline_path.expect("--write-baseline requires --baseline");
+        let keys: Vec<String> = duplicate_values
+            .iter()
+            .map(|((kind, value), _)| baseline_key(*kind, &hex_of(value)))
+            .collect();
+        write_baseline(&baseline_path, keys)?;
+        println!(
+            "Wrote {} duplicate group(s) to baseline {}",
+            duplicate_values.len(),
+            baseline_path.display()
+        );
+        return Ok(());
+    }
 
-    for (value, locations) in value_map.iter() {
-        if locations.len() > 1 {
-            duplicates_found = true;
-            duplicate_groups += 1;
-            total_duplicates += locations.len();
+    let baselined_keys = match &baseline_path {
+        Some(path) if path.exists() => load_baseline(path)?,
+        _ => HashSet::new(),
+    };
 
-            println!("{}", "=".repeat(80));
-            println!("DUPLICATE FOUND: {} occurrences", locations.len());
-            println!("Byte value: {:?}", value);
-            println!(
-                "Hex representation: {}",
-                value
-                    .iter()
-                    .map(|b| format!("{:02x}", b))
-                    .collect::<String>()
-            );
-            println!("{}", "-".repeat(80));
-
-            for loc in locations {
-                println!("  {}:{} - {}", loc.file.display(), loc.line, loc.name);
+    let mut groups: Vec<DuplicateGroup> = duplicate_values
+        .iter()
+        .map(|((kind, value), locations)| {
+            let hex_value = hex_of(value);
+            let baselined = baselined_keys.contains(&baseline_key(*kind, &hex_value));
+            DuplicateGroup {
+                kind: *kind,
+                hex_value,
+                locations,
+                baselined,
             }
-            println!();
+        })
+        .collect();
+    groups.sort_by(|a, b| (a.kind, &a.hex_value).cmp(&(b.kind, &b.hex_value)));
+
+    let new_duplicate_groups = groups.iter().filter(|g| !g.baselined).count();
+
+    if format == "json" {
+        let report = Report {
+            scanned_files: file_count,
+            scanned_constants: constant_count,
+            new_duplicate_groups,
+            duplicate_groups: groups,
+        };
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        println!(
+            "Scanned {} files, found {} constants",
+            file_count, constant_count
+        );
+        println!("Analyzing for duplicates...\n");
+
+        if groups.is_empty() {
+            println!("✓ No duplicate constants found!");
+        } else {
+            let mut total_duplicates = 0;
+            for group in &groups {
+                total_duplicates += group.locations.len();
+
+                println!("{}", "=".repeat(80));
+                println!(
+                    "DUPLICATE FOUND: {} occurrences of a {:?}{}",
+                    group.locations.len(),
+                    group.kind,
+                    if group.baselined { " (baselined)" } else { "" }
+                );
+                println!("Hex representation: {}", group.hex_value);
+                println!("{}", "-".repeat(80));
+
+                for loc in group.locations {
+                    println!("  {}:{} - {}", loc.file.display(), loc.line, loc.name);
+                }
+                println!();
+            }
+
+            println!("{}", "=".repeat(80));
+            println!("SUMMARY:");
+            println!("  {} duplicate groups found", groups.len());
+            println!("  {} total duplicate constants", total_duplicates);
+            println!("  {} newly introduced (not in baseline)", new_duplicate_groups);
+            println!("  Consider consolidating these duplicates to avoid redundancy");
         }
     }
 
-    if !duplicates_found {
-        println!("✓ No duplicate byte constants found!");
-    } else {
-        println!("{}", "=".repeat(80));
-        println!("SUMMARY:");
-        println!("  {} duplicate groups found", duplicate_groups);
-        println!("  {} total duplicate constants", total_duplicates);
-        println!("  Consider consolidating these duplicates to avoid redundancy");
+    if new_duplicate_groups > 0 {
         std::process::exit(1); // Exit with error code for CI/CD
     }
 